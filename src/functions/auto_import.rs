@@ -0,0 +1,181 @@
+use crate::items::module_item::ModuleItem;
+use crate::items::source_file::SourceFile;
+use crate::items::symbol_table::{ModuleSymbols, Namespace, SymbolTable};
+use crate::items::use_item::UseItem;
+use crate::utils::create_use;
+use crate::utils::path::Path;
+use crate::walkers::scope::{Scope, ScopeVisitor, ScopeWalker};
+use std::collections::{HashMap, HashSet};
+use syn::{PathArguments, PathSegment};
+
+/// Names brought in scope by the std prelude, which an import would shadow.
+const PRELUDE_NAMES: [&str; 39] = [
+    "Copy", "Send", "Sized", "Sync", "Unpin", "Drop", "Fn", "FnMut", "FnOnce", "drop", "Box",
+    "ToOwned", "Clone", "PartialEq", "PartialOrd", "Eq", "Ord", "AsRef", "AsMut", "Into", "From",
+    "Default", "Iterator", "Extend", "IntoIterator", "DoubleEndedIterator", "ExactSizeIterator",
+    "Option", "Some", "None", "Result", "Ok", "Err", "String", "ToString", "Vec", "TryFrom",
+    "TryInto", "FromIterator",
+];
+
+#[derive(Default)]
+struct Candidate {
+    paths: Vec<Path>,
+    is_shadowed: bool,
+}
+
+/// Collects, then shortens, the fully qualified paths of a module.
+struct AutoImporter {
+    candidates: HashMap<String, Candidate>,
+    imports: HashMap<String, Path>,
+    symbols: ModuleSymbols,
+    local_idents: HashSet<String>,
+    is_rewriting: bool,
+}
+
+impl AutoImporter {
+    /// Index of the segment to import: the first type-like segment (e.g. `HashMap` in
+    /// `std::collections::HashMap::new`) or the last one (e.g. `swap` in `std::mem::swap`).
+    /// Paths must start with the name of an external crate, not with a module or an import of
+    /// the current module.
+    fn import_index(&self, path: &syn::Path) -> Option<usize> {
+        if path.segments.len() < 2 {
+            return None;
+        }
+        let first_segment = path.segments[0].ident.to_string();
+        let is_local_name = match self.symbols.lookup_in(&first_segment, Namespace::Type) {
+            Some(symbol) => !symbol.is_extern_crate(),
+            None => self.local_idents.contains(&first_segment),
+        };
+        let is_crate_name = first_segment.starts_with(|char: char| char.is_lowercase())
+            && !matches!(first_segment.as_str(), "crate" | "self" | "super")
+            && !is_local_name;
+        if !is_crate_name {
+            return None;
+        }
+        let index = path.segments.iter()
+            .skip(1)
+            .position(|segment| segment.ident.to_string().starts_with(char::is_uppercase))
+            .map(|index| index + 1)
+            .unwrap_or(path.segments.len() - 1);
+        let has_arguments = path.segments.iter()
+            .take(index)
+            .any(|segment| !matches!(segment.arguments, PathArguments::None));
+        match has_arguments {
+            true => None,
+            false => Some(index),
+        }
+    }
+
+    fn import_path(path: &syn::Path, index: usize) -> Path {
+        let segments = path.segments.iter()
+            .take(index + 1)
+            .map(|segment| PathSegment::from(segment.ident.clone()))
+            .collect::<Vec<_>>();
+        Path::from(segments)
+    }
+}
+
+impl ScopeVisitor for AutoImporter {
+    fn visit_path(&mut self, path: &mut syn::Path, namespace: Namespace, scope: &Scope) {
+        let index = match self.import_index(path) {
+            Some(value) => value,
+            None => return,
+        };
+        let ident = path.segments[index].ident.to_string();
+        let import_path = Self::import_path(path, index);
+        if self.is_rewriting {
+            if self.imports.get(&ident) == Some(&import_path) {
+                path.leading_colon = None;
+                path.segments = path.segments.iter()
+                    .skip(index)
+                    .cloned()
+                    .collect();
+            }
+            return;
+        }
+        let namespace = match index == path.segments.len() - 1 {
+            true => namespace,
+            false => Namespace::Type,
+        };
+        let candidate = self.candidates.entry(ident.clone()).or_default();
+        if !candidate.paths.contains(&import_path) {
+            candidate.paths.push(import_path);
+        }
+        candidate.is_shadowed |= scope.is_local(&ident, namespace);
+    }
+}
+
+/// Whether `ident`, imported from `import_path`, is already in scope through the prelude or a
+/// glob import.
+fn is_in_scope(ident: &str, import_path: &Path, globs: &[Path], local_idents: &HashSet<String>) -> bool {
+    let parent = Path::from(import_path.iter().take(import_path.len() - 1).cloned().collect::<Vec<_>>());
+    PRELUDE_NAMES.contains(&ident) || local_idents.contains(ident) || globs.contains(&parent)
+}
+
+/// Names of the crate modules and idents brought in scope by the glob imports of `symbols`
+/// targeting them.
+fn local_idents(symbols: &ModuleSymbols, symbol_table: &SymbolTable) -> HashSet<String> {
+    let glob_idents = symbols.globs().iter()
+        .filter_map(|glob| symbol_table.module_of(glob))
+        .flat_map(|module| module.symbols().iter().map(|symbol| symbol.ident.clone()));
+    symbol_table.modules.iter()
+        .map(|module| module.module.clone())
+        .filter(|module| !module.is_empty())
+        .chain(glob_idents)
+        .collect()
+}
+
+fn internal_from_module(module: &mut ModuleItem, local_idents: HashSet<String>) -> Vec<Path> {
+    let mut importer = AutoImporter {
+        candidates: HashMap::new(),
+        imports: HashMap::new(),
+        symbols: ModuleSymbols::new(module),
+        local_idents,
+        is_rewriting: false,
+    };
+    ScopeWalker::new(&mut importer).walk_module(&mut module.clone());
+    let mut new_imports = Vec::new();
+    let mut candidates = importer.candidates.drain().collect::<Vec<_>>();
+    candidates.sort_by(|(a, _), (b, _)| a.cmp(b));
+    for (ident, candidate) in candidates {
+        if candidate.is_shadowed || candidate.paths.len() > 1 {
+            continue;
+        }
+        let import_path = candidate.paths.into_iter().next().unwrap();
+        match importer.symbols.lookup(&ident) {
+            Some(symbol) if symbol.is_import() && symbol.path == import_path => {}
+            Some(_) => continue,
+            None if is_in_scope(&ident, &import_path, importer.symbols.globs(), &importer.local_idents) => continue,
+            None => new_imports.push(ident.clone()),
+        }
+        importer.imports.insert(ident, import_path);
+    }
+    importer.is_rewriting = true;
+    ScopeWalker::new(&mut importer).walk_module(module);
+    new_imports.iter()
+        .map(|ident| {
+            let path = importer.imports[ident].clone();
+            module.use_items.push(UseItem(create_use(&path)));
+            path
+        })
+        .collect()
+}
+
+/// Shorten the fully qualified paths of a module (e.g. `std::collections::HashMap`) to their
+/// imported segment and insert the matching `use` items. Paths are left alone when the segment
+/// is ambiguous (imported from several paths), shadowed by a local binding, colliding with an
+/// item or import of the module or already in scope through the prelude or a glob import.
+/// Returns the inserted imports.
+pub fn from_module(module: &mut ModuleItem) -> Vec<Path> {
+    internal_from_module(module, HashSet::new())
+}
+
+/// Same as [`from_module`] for every module, paths starting with a crate module or with an ident
+/// of a glob import of the crate being left alone.
+pub fn from_source_file(source_file: &mut SourceFile) {
+    let symbol_table = SymbolTable::new(source_file);
+    for module in source_file.modules.iter_mut() {
+        let symbols = ModuleSymbols::new(module);
+        internal_from_module(module, local_idents(&symbols, &symbol_table));
+    }
+}
@@ -0,0 +1,150 @@
+use crate::functions::auto_import;
+use crate::test_utils::fixtures::source_file;
+use crate::utils::parsing::TokenStreamExt;
+use crate::utils::path::Path;
+use pretty_assertions::assert_eq;
+use quote::quote;
+use rstest::rstest;
+
+#[rstest]
+fn from_module() {
+    let quote = quote! {
+        use std::fmt::Display;
+
+        struct Rc;
+
+        struct Cache {
+            values: std::collections::HashMap<String, std::fmt::Display>,
+            shared: std::rc::Rc<u32>,
+            ordered: std::collections::BTreeMap<u32, u32>,
+            other: other::collections::BTreeMap<u32, u32>,
+        }
+
+        fn build(mut a: u32, mut b: u32) -> Cache {
+            std::mem::swap(&mut a, &mut b);
+            let values = std::collections::HashMap::new();
+            let take = 1;
+            std::mem::take(&mut a);
+            Cache {
+                values,
+                shared: std::rc::Rc::new(take),
+            }
+        }
+    };
+    let mut source_file = quote.parse();
+    let imports = auto_import::from_module(&mut source_file.modules[0]);
+    let expected_quote = quote! {
+        use std::fmt::Display;
+        use std::collections::HashMap;
+        use std::mem::swap;
+
+        struct Rc;
+
+        struct Cache {
+            values: HashMap<String, Display>,
+            shared: std::rc::Rc<u32>,
+            ordered: std::collections::BTreeMap<u32, u32>,
+            other: other::collections::BTreeMap<u32, u32>,
+        }
+
+        fn build(mut a: u32, mut b: u32) -> Cache {
+            swap(&mut a, &mut b);
+            let values = HashMap::new();
+            let take = 1;
+            std::mem::take(&mut a);
+            Cache {
+                values,
+                shared: std::rc::Rc::new(take),
+            }
+        }
+    };
+    let expected_source_file = expected_quote.parse();
+    assert_eq!(expected_source_file, source_file);
+    assert_eq!(
+        vec![
+            Path::new("std").join("collections").join("HashMap").to_owned(),
+            Path::new("std").join("mem").join("swap").to_owned(),
+        ],
+        imports
+    );
+}
+
+#[rstest]
+fn from_module_keeps_prelude_and_glob_names() {
+    let quote = quote! {
+        use std::io::*;
+
+        struct X;
+
+        fn a() -> Result<u32, ()> {
+            Ok(1)
+        }
+
+        impl std::fmt::Display for X {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                Ok(())
+            }
+        }
+
+        fn b(read: std::io::Stdin) -> std::option::Option<u32> {
+            None
+        }
+    };
+    let mut source_file = quote.parse();
+    let imports = auto_import::from_module(&mut source_file.modules[0]);
+    let expected_quote = quote! {
+        use std::io::*;
+        use std::fmt::Display;
+        use std::fmt::Formatter;
+
+        struct X;
+
+        fn a() -> Result<u32, ()> {
+            Ok(1)
+        }
+
+        impl Display for X {
+            fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+                Ok(())
+            }
+        }
+
+        fn b(read: std::io::Stdin) -> std::option::Option<u32> {
+            None
+        }
+    };
+    let expected_source_file = expected_quote.parse();
+    assert_eq!(expected_source_file, source_file);
+    assert_eq!(
+        vec![
+            Path::new("std").join("fmt").join("Display").to_owned(),
+            Path::new("std").join("fmt").join("Formatter").to_owned(),
+        ],
+        imports
+    );
+}
+
+#[rstest]
+fn from_source_file_keeps_local_module_paths() {
+    let mut source_file = source_file(vec![
+        ("", quote! {
+            use models::*;
+
+            fn find() -> models::User {
+                models::User::new()
+            }
+
+            fn other() -> types::Id {
+                types::Id::new()
+            }
+        }),
+        ("models", quote! {
+            pub use crate::types;
+
+            pub struct User;
+        }),
+    ]);
+    let expected_source_file = source_file.clone();
+    auto_import::from_source_file(&mut source_file);
+    assert_eq!(expected_source_file, source_file);
+}
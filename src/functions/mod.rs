@@ -1,7 +1,26 @@
+pub mod auto_import;
+#[cfg(test)]
+#[path = "./auto_import_test.rs"]
+mod auto_import_test;
+
+pub mod qualify_path;
+#[cfg(test)]
+#[path = "./qualify_path_test.rs"]
+mod qualify_path_test;
+
 pub mod rename;
+#[cfg(test)]
+#[path = "./rename_test.rs"]
+mod rename_test;
+
 pub mod transform;
 
 pub mod trim_path;
 #[cfg(test)]
 #[path = "./trim_path_test.rs"]
 mod trim_path_test;
+
+pub mod visibility;
+#[cfg(test)]
+#[path = "./visibility_test.rs"]
+mod visibility_test;
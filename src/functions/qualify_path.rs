@@ -0,0 +1,138 @@
+use crate::items::module_item::ModuleItem;
+use crate::items::source_file::SourceFile;
+use crate::items::symbol_table::{ModuleSymbols, Namespace, SymbolKind, SymbolTable};
+use crate::utils::create_ident;
+use crate::walkers::scope::{Scope, ScopeVisitor, ScopeWalker};
+use std::collections::HashSet;
+use syn::{PathArguments, PathSegment};
+
+/// Rewrites the paths starting with an imported ident to their absolute form.
+struct PathQualifier {
+    symbols: ModuleSymbols,
+    /// Idents of the crate the module may refer to without a symbol of its own, e.g. the modules
+    /// declared with `mod module;` in the crate root.
+    local_idents: HashSet<String>,
+    qualified_idents: HashSet<String>,
+}
+
+impl PathQualifier {
+    /// Absolute segments of an imported ident, following imports and extern crates of the module.
+    /// The `bool` tells whether the path points to an external crate. Imports relative to an
+    /// item of the module are qualified with `self`, those whose first segment is neither a
+    /// symbol of the module nor a local ident are taken as relative to an external crate.
+    fn qualify(&self, ident: &str, visited_idents: &mut HashSet<String>) -> Option<(Vec<PathSegment>, bool)> {
+        if !visited_idents.insert(ident.to_string()) {
+            return None;
+        }
+        let symbol = self.symbols.lookup(ident)?;
+        let mut segments = symbol.path.iter().cloned().collect::<Vec<_>>();
+        match symbol.kind {
+            SymbolKind::ExternCrate => return Some((segments, true)),
+            SymbolKind::Import => {}
+            _ => return None,
+        }
+        let first_segment = segments[0].ident.to_string();
+        if matches!(first_segment.as_str(), "crate" | "self" | "super") {
+            return Some((segments, false));
+        }
+        if let Some(symbol) = self.symbols.lookup_in(&first_segment, Namespace::Type) {
+            if symbol.is_item() {
+                segments.insert(0, PathSegment::from(create_ident("self")));
+                return Some((segments, false));
+            }
+        }
+        match self.qualify(&first_segment, visited_idents) {
+            Some((mut prefix, is_external)) => {
+                prefix.extend(segments.drain(1..));
+                Some((prefix, is_external))
+            }
+            None if self.symbols.lookup(&first_segment).is_some() => None,
+            None if self.local_idents.contains(&first_segment) => None,
+            None => Some((segments, true)),
+        }
+    }
+}
+
+impl ScopeVisitor for PathQualifier {
+    fn visit_path(&mut self, path: &mut syn::Path, namespace: Namespace, scope: &Scope) {
+        if path.leading_colon.is_some() {
+            return;
+        }
+        let first_segment = match path.segments.first() {
+            Some(value) => value.clone(),
+            None => return,
+        };
+        let ident = first_segment.ident.to_string();
+        let namespace = match path.segments.len() {
+            1 => namespace,
+            _ => Namespace::Type,
+        };
+        if scope.is_local(&ident, namespace) {
+            return;
+        }
+        let mut visited_idents = HashSet::new();
+        let (mut segments, is_external) = match self.qualify(&ident, &mut visited_idents) {
+            Some(value) => value,
+            None => return,
+        };
+        if !matches!(first_segment.arguments, PathArguments::None) {
+            segments.last_mut().unwrap().arguments = first_segment.arguments;
+        }
+        segments.extend(path.segments.iter().skip(1).cloned());
+        path.segments = segments.into_iter().collect();
+        if is_external {
+            path.leading_colon = Some(Default::default());
+        }
+        // the imports the path went through, e.g. `fmt` for `use fmt::Debug`
+        self.qualified_idents.extend(visited_idents);
+    }
+}
+
+/// Rewrite every path of a module starting with an imported ident to its absolute form, e.g.
+/// `HashMap` to `::std::collections::HashMap` or `fmt::Display` to `::std::fmt::Display`, then
+/// drop the imports it went through once no longer referenced. This is the inverse of
+/// [`crate::functions::trim_path`]. Paths brought by glob imports are left alone and re-exports
+/// (`pub use`) are never dropped, nor are the imports possibly needed as traits to call methods.
+/// Imports whose first segment is not a symbol of the module are taken as relative to an
+/// external crate and get a leading `::`, imports of items of the module are qualified with `self`.
+pub fn from_module(module: &mut ModuleItem) {
+    internal_from_module(module, HashSet::new());
+}
+
+/// Same as [`from_module`] for every module, the modules of the source file never being taken
+/// for external crates.
+pub fn from_source_file(source_file: &mut SourceFile) {
+    let symbol_table = SymbolTable::new(source_file);
+    let module_names = symbol_table.modules.iter()
+        .map(|module| module.module.clone())
+        .filter(|module| !module.is_empty())
+        .collect::<HashSet<_>>();
+    for module in source_file.modules.iter_mut() {
+        internal_from_module(module, module_names.clone());
+    }
+}
+
+fn internal_from_module(module: &mut ModuleItem, local_idents: HashSet<String>) {
+    let mut qualifier = PathQualifier {
+        symbols: ModuleSymbols::new(module),
+        local_idents,
+        qualified_idents: HashSet::new(),
+    };
+    ScopeWalker::new(&mut qualifier).walk_module(module);
+    loop {
+        // Dropping an import may leave unused the one it was relative to (e.g. `use fmt::Debug`).
+        let unneeded_idents = module.remove_unused_imports(true).unused.into_iter()
+            .map(|import| import.ident)
+            .filter(|ident| qualifier.qualified_idents.contains(ident))
+            .collect::<HashSet<_>>();
+        if unneeded_idents.is_empty() {
+            break;
+        }
+        for use_item in module.use_items.iter_mut() {
+            if let syn::Visibility::Inherited = use_item.0.vis {
+                use_item.retain_imports(|ident| !unneeded_idents.contains(ident));
+            }
+        }
+        module.use_items.retain(|use_item| !use_item.is_empty());
+    }
+}
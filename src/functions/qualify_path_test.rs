@@ -0,0 +1,173 @@
+use crate::functions::qualify_path;
+use crate::test_utils::fixtures::source_file;
+use crate::utils::parsing::TokenStreamExt;
+use pretty_assertions::assert_eq;
+use quote::quote;
+use rstest::rstest;
+
+#[rstest]
+fn from_module() {
+    let quote = quote! {
+        extern crate serde as serde_crate;
+
+        use std::collections::HashMap;
+        use std::fmt;
+        use fmt::Debug as Dbg;
+        use serde_crate::Serialize;
+        use std::io::*;
+        use std::rc::Rc;
+        use crate::models::Model;
+        pub use crate::models::Role;
+
+        #[derive(Serialize)]
+        struct Cache {
+            values: HashMap<String, Model>,
+            role: Role,
+        }
+
+        impl fmt::Display for Cache {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                Ok(())
+            }
+        }
+
+        fn build<Rc>(value: Rc, debug: &dyn Dbg) -> Cache {
+            let values = HashMap::<String, Model>::new();
+            Cache { values, role: Role::default() }
+        }
+    };
+    let mut source_file = quote.parse();
+    qualify_path::from_module(&mut source_file.modules[0]);
+    let expected_quote = quote! {
+        extern crate serde as serde_crate;
+
+        use serde_crate::Serialize;
+        use std::io::*;
+        use std::rc::Rc;
+        pub use crate::models::Role;
+
+        #[derive(Serialize)]
+        struct Cache {
+            values: ::std::collections::HashMap<String, crate::models::Model>,
+            role: crate::models::Role,
+        }
+
+        impl ::std::fmt::Display for Cache {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                Ok(())
+            }
+        }
+
+        fn build<Rc>(value: Rc, debug: &dyn ::std::fmt::Debug) -> Cache {
+            let values = ::std::collections::HashMap::<String, crate::models::Model>::new();
+            Cache { values, role: crate::models::Role::default() }
+        }
+    };
+    let expected_source_file = expected_quote.parse();
+    assert_eq!(expected_source_file, source_file);
+}
+
+#[rstest]
+fn from_module_with_local_items() {
+    let quote = quote! {
+        use Kind::A;
+        use models::User;
+        use unknown::Other;
+
+        enum Kind {
+            A,
+        }
+
+        fn kind() -> Kind {
+            A
+        }
+
+        fn user() -> User {
+            User::new(Other)
+        }
+    };
+    let mut source_file = quote.parse();
+    source_file.modules[0].push_item(syn::parse_quote! {
+        mod models {
+            pub struct User;
+        }
+    });
+    qualify_path::from_module(&mut source_file.modules[0]);
+    let expected_quote = quote! {
+        enum Kind {
+            A,
+        }
+
+        fn kind() -> Kind {
+            self::Kind::A
+        }
+
+        fn user() -> self::models::User {
+            self::models::User::new(::unknown::Other)
+        }
+    };
+    let mut expected_source_file = expected_quote.parse();
+    expected_source_file.modules[0].push_item(syn::parse_quote! {
+        mod models {
+            pub struct User;
+        }
+    });
+    assert_eq!(expected_source_file, source_file);
+}
+
+#[rstest]
+fn from_module_with_method_calls() {
+    let quote = quote! {
+        use std::collections::HashMap;
+        use serde::Serialize;
+
+        fn count<T: Serialize>(values: HashMap<String, T>) -> usize {
+            values.len()
+        }
+    };
+    let mut source_file = quote.parse();
+    qualify_path::from_module(&mut source_file.modules[0]);
+    let expected_quote = quote! {
+        use serde::Serialize;
+
+        fn count<T: ::serde::Serialize>(values: ::std::collections::HashMap<String, T>) -> usize {
+            values.len()
+        }
+    };
+    let expected_source_file = expected_quote.parse();
+    assert_eq!(expected_source_file, source_file);
+}
+
+#[rstest]
+fn from_source_file_with_crate_modules() {
+    let mut source_file = source_file(vec![
+        ("lib", quote! {
+            use models::User;
+            use serde::Serialize;
+
+            #[derive(Serialize)]
+            struct Session {
+                user: User,
+            }
+        }),
+        ("models", quote! {
+            pub struct User;
+        }),
+    ]);
+    qualify_path::from_source_file(&mut source_file);
+    let expected_source_file = self::source_file(vec![
+        ("lib", quote! {
+            use models::User;
+            use serde::Serialize;
+
+            #[derive(Serialize)]
+            struct Session {
+                user: User,
+            }
+        }),
+        ("models", quote! {
+            pub struct User;
+        }),
+    ]);
+    assert_eq!(expected_source_file, source_file);
+}
@@ -1,9 +1,17 @@
 use crate::items::item::{Item, ItemTrait};
+use crate::items::module_item::ModuleItem;
+use crate::items::source_file::SourceFile;
+use crate::items::symbol_table::{ModuleSymbols, Namespace, SymbolTable};
 use crate::utils::create_ident;
 use crate::utils::path::Path;
+use crate::utils::path_pattern::PathPattern;
 use crate::walkers::expr::ExprWalker;
+use crate::walkers::path::PathWalker;
+use crate::walkers::scope::{Scope, ScopeVisitor, ScopeWalker};
 use crate::walkers::type_::TypeWalker;
 use crate::walkers::Context;
+use quote::ToTokens;
+use syn::{ExprMethodCall, Ident, Member, PathSegment, UseTree};
 
 fn create_context(from: String, to: String) -> Context {
     let path_predicate_from = from.clone();
@@ -35,9 +43,34 @@ fn create_context(from: String, to: String) -> Context {
     }
 }
 
+fn create_pattern_context(pattern: PathPattern, to: String) -> Context {
+    Context {
+        type_predicate: None,
+        expr_predicate: None,
+        stmt_predicate: None,
+        path_predicate: Some(Box::new(move |value| {
+            PathWalker::for_each_argument_type(value, |type_| from_type_matching(type_, &pattern, &to));
+            if !pattern.matches(&Path::from(&*value)) {
+                return false;
+            }
+            value.segments.last_mut().unwrap().ident = create_ident(to.clone());
+            true
+        })),
+        ident_predicate: None,
+    }
+}
+
+/// Rename the item `from` within `item`, in any namespace, leaving alone local bindings, generic
+/// parameters, fields and methods that share the same spelling. Use [`rename_symbol`] or
+/// [`rename_item`] to rename it across a module or a source file.
 pub fn from_item(item: &mut Item, from: &String, to: &String) {
-    let mut context = create_context(from.clone(), to.clone());
-    item.walk(&mut context);
+    let mut renamer = SymbolRenamer {
+        module: String::new(),
+        namespaces: vec![Namespace::Type, Namespace::Value, Namespace::Macro],
+        from: from.clone(),
+        to: to.clone(),
+    };
+    ScopeWalker::new(&mut renamer).walk_item(item);
 }
 
 pub fn from_type(type_: &mut syn::Type, from: &String, to: &String) {
@@ -48,4 +81,414 @@ pub fn from_type(type_: &mut syn::Type, from: &String, to: &String) {
 pub fn from_expr(expr: &mut syn::Expr, from: &String, to: &String) {
     let mut context = create_context(from.clone(), to.clone());
     ExprWalker::walk(expr, &mut context)
+}
+
+/// Rename the last segment of every path matching `pattern`, e.g. `**::models::Model` renamed
+/// to `Entity` turns `crate::models::Model` into `crate::models::Entity`.
+pub fn from_item_matching(item: &mut Item, pattern: &PathPattern, to: &str) {
+    let mut context = create_pattern_context(pattern.clone(), to.to_string());
+    item.walk(&mut context);
+    item.write_functions();
+}
+
+pub fn from_type_matching(type_: &mut syn::Type, pattern: &PathPattern, to: &str) {
+    let mut context = create_pattern_context(pattern.clone(), to.to_string());
+    TypeWalker::walk(type_, &mut context)
+}
+
+pub fn from_expr_matching(expr: &mut syn::Expr, pattern: &PathPattern, to: &str) {
+    let mut context = create_pattern_context(pattern.clone(), to.to_string());
+    ExprWalker::walk(expr, &mut context)
+}
+
+struct SymbolRenamer {
+    module: String,
+    namespaces: Vec<Namespace>,
+    from: String,
+    to: String,
+}
+
+impl SymbolRenamer {
+    /// Index of the segment naming a module level item in `Foo`, `Foo::new`, `self::Foo` or
+    /// `crate::module::Foo`.
+    fn item_segment_index(&self, path: &syn::Path) -> Option<usize> {
+        if path.leading_colon.is_some() {
+            return None;
+        }
+        let first_segment = path.segments.first()?;
+        let index = match first_segment.ident.to_string().as_str() {
+            "self" => 1,
+            "crate" if self.module.is_empty() => 1,
+            "crate" => {
+                let second_segment = path.segments.iter().nth(1)?;
+                if second_segment.ident != self.module {
+                    return None;
+                }
+                2
+            }
+            "super" => return None,
+            _ => 0,
+        };
+        match index < path.segments.len() {
+            true => Some(index),
+            false => None,
+        }
+    }
+}
+
+impl ScopeVisitor for SymbolRenamer {
+    fn visit_item_ident(&mut self, ident: &mut Ident, namespaces: &[Namespace], _scope: &Scope) {
+        let is_in_namespace = namespaces.iter()
+            .any(|namespace| self.namespaces.contains(namespace));
+        if is_in_namespace && *ident == self.from {
+            *ident = create_ident(&self.to);
+        }
+    }
+
+    fn visit_path(&mut self, path: &mut syn::Path, namespace: Namespace, scope: &Scope) {
+        let index = match self.item_segment_index(path) {
+            Some(value) => value,
+            None => return,
+        };
+        let segment_namespace = match index == path.segments.len() - 1 {
+            true => namespace,
+            false => Namespace::Type,
+        };
+        if !self.namespaces.contains(&segment_namespace) {
+            return;
+        }
+        if index == 0 && scope.is_local(&self.from, segment_namespace) {
+            return;
+        }
+        let segment = &mut path.segments[index];
+        if segment.ident == self.from {
+            segment.ident = create_ident(&self.to);
+        }
+    }
+}
+
+/// Rename the item `from` of a module and its references, leaving alone local bindings, generic
+/// parameters, fields, methods and items of other namespaces that share the same spelling.
+pub fn rename_symbol(module: &mut ModuleItem, namespace: Namespace, from: &str, to: &str) {
+    let namespaces = match ModuleSymbols::new(module).lookup_in(from, namespace) {
+        Some(symbol) if symbol.is_item() => symbol.namespaces.clone(),
+        _ => vec![namespace],
+    };
+    let mut renamer = SymbolRenamer {
+        module: module.file_name.clone(),
+        namespaces,
+        from: from.to_string(),
+        to: to.to_string(),
+    };
+    ScopeWalker::new(&mut renamer).walk_module(module);
+}
+
+fn is_path_of(path: &syn::Path, ident: &str) -> bool {
+    path.segments.last()
+        .map(|segment| segment.ident == ident)
+        .unwrap_or(false)
+}
+
+struct FieldRenamer {
+    type_: String,
+    from: String,
+    to: String,
+}
+
+impl ScopeVisitor for FieldRenamer {
+    fn visit_field(&mut self, owner: Option<&syn::Path>, member: &mut Member, _scope: &Scope) {
+        let is_owned = owner
+            .map(|owner| is_path_of(owner, &self.type_))
+            .unwrap_or(false);
+        if !is_owned {
+            return;
+        }
+        if let Member::Named(ident) = member {
+            if *ident == self.from {
+                *ident = create_ident(&self.to);
+            }
+        }
+    }
+}
+
+/// Rename the field `from` of the struct `type_`, along with struct literals, `Self { .. }`
+/// constructions, struct patterns and field accesses whose receiver is known to be `type_`.
+pub fn rename_field(source_file: &mut SourceFile, type_: &str, from: &str, to: &str) {
+    let mut renamer = FieldRenamer {
+        type_: type_.to_string(),
+        from: from.to_string(),
+        to: to.to_string(),
+    };
+    for module in source_file.modules.iter_mut() {
+        ScopeWalker::new(&mut renamer).walk_module(module);
+    }
+}
+
+struct VariantRenamer {
+    enum_: String,
+    from: String,
+    to: String,
+}
+
+impl ScopeVisitor for VariantRenamer {
+    fn visit_path(&mut self, path: &mut syn::Path, _namespace: Namespace, scope: &Scope) {
+        let resolved_path = scope.resolve_self(path);
+        let segment_count = resolved_path.segments.len();
+        if segment_count < 2 {
+            return;
+        }
+        let is_variant = resolved_path.segments[segment_count - 2].ident == self.enum_
+            && resolved_path.segments[segment_count - 1].ident == self.from;
+        if !is_variant {
+            return;
+        }
+        let segment = path.segments.last_mut().unwrap();
+        segment.ident = create_ident(&self.to);
+    }
+
+    fn visit_variant(&mut self, owner: &Ident, variant: &mut Ident, _scope: &Scope) {
+        if *owner == self.enum_ && *variant == self.from {
+            *variant = create_ident(&self.to);
+        }
+    }
+}
+
+/// Rename the variant `from` of the enum `enum_` and every `Enum::Variant` or `Self::Variant`
+/// path targeting it in expressions, patterns and struct literals.
+pub fn rename_variant(source_file: &mut SourceFile, enum_: &str, from: &str, to: &str) {
+    let mut renamer = VariantRenamer {
+        enum_: enum_.to_string(),
+        from: from.to_string(),
+        to: to.to_string(),
+    };
+    for module in source_file.modules.iter_mut() {
+        ScopeWalker::new(&mut renamer).walk_module(module);
+    }
+}
+
+/// Method call left untouched by [`rename_method`] because its receiver type is unknown.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MethodCallSite {
+    pub module: String,
+    pub item: Option<String>,
+    pub call: String,
+}
+
+struct MethodRenamer {
+    type_: String,
+    from: String,
+    to: String,
+    module: String,
+    item: Option<String>,
+    ambiguous_call_sites: Vec<MethodCallSite>,
+}
+
+impl ScopeVisitor for MethodRenamer {
+    fn visit_path(&mut self, path: &mut syn::Path, _namespace: Namespace, scope: &Scope) {
+        let resolved_path = scope.resolve_self(path);
+        let segment_count = resolved_path.segments.len();
+        if segment_count < 2 {
+            return;
+        }
+        let is_method = resolved_path.segments[segment_count - 2].ident == self.type_
+            && resolved_path.segments[segment_count - 1].ident == self.from;
+        if !is_method {
+            return;
+        }
+        let segment = path.segments.last_mut().unwrap();
+        segment.ident = create_ident(&self.to);
+    }
+
+    fn visit_associated_item(
+        &mut self,
+        owner: &syn::Path,
+        trait_: Option<&syn::Path>,
+        ident: &mut Ident,
+        _scope: &Scope,
+    ) {
+        // methods of trait implementations are named by the trait
+        if trait_.is_some() || !is_path_of(owner, &self.type_) {
+            return;
+        }
+        if *ident == self.from {
+            *ident = create_ident(&self.to);
+        }
+    }
+
+    fn visit_method_call(&mut self, receiver: Option<&syn::Path>, call: &mut ExprMethodCall, _scope: &Scope) {
+        if call.method != self.from {
+            return;
+        }
+        match receiver {
+            Some(receiver) if is_path_of(receiver, &self.type_) => {
+                call.method = create_ident(&self.to);
+            }
+            Some(_) => {}
+            None => {
+                self.ambiguous_call_sites.push(MethodCallSite {
+                    module: self.module.clone(),
+                    item: self.item.clone(),
+                    call: call.to_token_stream().to_string(),
+                });
+            }
+        }
+    }
+}
+
+/// Rename the method `from` of `type_` in its inherent impls, in `Type::from` and `Self::from`
+/// paths, and in method calls whose receiver is `self` within the impls of `type_` or a binding
+/// typed as `type_`. Method calls with an unknown receiver type are left untouched and returned.
+pub fn rename_method(source_file: &mut SourceFile, type_: &str, from: &str, to: &str) -> Vec<MethodCallSite> {
+    let mut renamer = MethodRenamer {
+        type_: type_.to_string(),
+        from: from.to_string(),
+        to: to.to_string(),
+        module: String::new(),
+        item: None,
+        ambiguous_call_sites: Vec::new(),
+    };
+    for module in source_file.modules.iter_mut() {
+        renamer.module = module.file_name.clone();
+        for index in 0..module.items.len() {
+            renamer.item = module.items[index].try_ident();
+            let mut walker = ScopeWalker::new(&mut renamer);
+            walker.enter_module(module);
+            walker.walk_item(&mut module.items[index]);
+        }
+    }
+    renamer.ambiguous_call_sites
+}
+
+fn path_idents(path: &Path) -> Vec<String> {
+    path.iter()
+        .map(|segment| segment.ident.to_string())
+        .collect()
+}
+
+struct ItemRenamer<'a> {
+    symbol_table: &'a SymbolTable,
+    module: String,
+    target: Vec<String>,
+    namespaces: Vec<Namespace>,
+    from: String,
+    to: String,
+    is_defining_module: bool,
+    is_local_name: bool,
+}
+
+impl ItemRenamer<'_> {
+    fn enter_module(&mut self, module: &ModuleItem, defining_module: &str) {
+        self.module = module.file_name.clone();
+        self.is_defining_module = module.file_name == defining_module;
+        self.is_local_name = self.resolves_to_target(vec![PathSegment::from(create_ident(&self.from))]);
+    }
+
+    fn resolves_to_target(&self, segments: Vec<PathSegment>) -> bool {
+        self.symbol_table.resolve_item(&self.module, &Path::from(segments))
+            .map(|path| path_idents(&path) == self.target)
+            .unwrap_or(false)
+    }
+
+    fn rename_use_tree(&self, tree: &mut UseTree, prefix: &mut Vec<PathSegment>) {
+        match tree {
+            UseTree::Path(value) => {
+                prefix.push(PathSegment::from(value.ident.clone()));
+                let is_renamed = value.ident == self.from && self.resolves_to_target(prefix.clone());
+                self.rename_use_tree(&mut value.tree, prefix);
+                prefix.pop();
+                if is_renamed {
+                    value.ident = create_ident(&self.to);
+                }
+            }
+            UseTree::Name(value) => {
+                let mut segments = prefix.clone();
+                segments.push(PathSegment::from(value.ident.clone()));
+                if value.ident == self.from && self.resolves_to_target(segments) {
+                    value.ident = create_ident(&self.to);
+                }
+            }
+            UseTree::Rename(value) => {
+                let mut segments = prefix.clone();
+                segments.push(PathSegment::from(value.ident.clone()));
+                if value.ident == self.from && self.resolves_to_target(segments) {
+                    value.ident = create_ident(&self.to);
+                }
+            }
+            UseTree::Glob(_) => {}
+            UseTree::Group(value) => {
+                for tree in value.items.iter_mut() {
+                    self.rename_use_tree(tree, prefix);
+                }
+            }
+        }
+    }
+}
+
+impl ScopeVisitor for ItemRenamer<'_> {
+    fn visit_item_ident(&mut self, ident: &mut Ident, namespaces: &[Namespace], _scope: &Scope) {
+        let is_in_namespace = namespaces.iter()
+            .any(|namespace| self.namespaces.contains(namespace));
+        if self.is_defining_module && is_in_namespace && *ident == self.from {
+            *ident = create_ident(&self.to);
+        }
+    }
+
+    fn visit_path(&mut self, path: &mut syn::Path, namespace: Namespace, scope: &Scope) {
+        if path.leading_colon.is_some() {
+            return;
+        }
+        let segments = path.segments.iter().cloned().collect::<Vec<_>>();
+        for (index, segment) in segments.iter().enumerate() {
+            if segment.ident != self.from {
+                continue;
+            }
+            let segment_namespace = match index == segments.len() - 1 {
+                true => namespace,
+                false => Namespace::Type,
+            };
+            if !self.namespaces.contains(&segment_namespace) {
+                continue;
+            }
+            let is_renamed = match index {
+                0 => self.is_local_name && !scope.is_local(&self.from, segment_namespace),
+                _ => self.resolves_to_target(segments[..=index].to_vec()),
+            };
+            if is_renamed {
+                path.segments[index].ident = create_ident(&self.to);
+            }
+        }
+    }
+}
+
+/// Rename the item `from` defined in `module` across every module of the source file: its
+/// definition, references, `use` items and `pub use` re-exports that import it, and qualified
+/// paths such as `crate::module::from` or `super::module::from`.
+pub fn rename_item(source_file: &mut SourceFile, module: &str, namespace: Namespace, from: &str, to: &str) {
+    let symbol_table = source_file.symbol_table();
+    let namespaces = match symbol_table.module(module).and_then(|symbols| symbols.lookup_in(from, namespace)) {
+        Some(symbol) if symbol.is_item() => symbol.namespaces.clone(),
+        _ => vec![namespace],
+    };
+    let mut target = Path::new("crate");
+    if !module.is_empty() {
+        target.join(module);
+    }
+    target.join(from);
+    let mut renamer = ItemRenamer {
+        symbol_table: &symbol_table,
+        module: String::new(),
+        target: path_idents(&target),
+        namespaces,
+        from: from.to_string(),
+        to: to.to_string(),
+        is_defining_module: false,
+        is_local_name: false,
+    };
+    for module_item in source_file.modules.iter_mut() {
+        renamer.enter_module(module_item, module);
+        ScopeWalker::new(&mut renamer).walk_module_in(module_item, &symbol_table);
+        for use_item in module_item.use_items.iter_mut() {
+            renamer.rename_use_tree(&mut use_item.0.tree, &mut Vec::new());
+        }
+    }
 }
\ No newline at end of file
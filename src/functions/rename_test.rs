@@ -0,0 +1,554 @@
+use crate::functions::rename;
+use crate::items::symbol_table::Namespace;
+use crate::test_utils::fixtures::source_file;
+use crate::utils::parsing::TokenStreamExt;
+use pretty_assertions::assert_eq;
+use quote::quote;
+use rstest::rstest;
+
+#[rstest]
+fn rename_symbol_in_type_namespace() {
+    let quote = quote! {
+        struct Config {
+            config: u32
+        }
+
+        impl Config {
+            fn new(config: u32) -> Self {
+                Config { config }
+            }
+        }
+
+        fn build<Config>(value: Config) -> Config {
+            value
+        }
+
+        fn main() {
+            let Config = 1;
+            let config = Config::new(Config);
+            let other: self::Config = crate::anonymous::Config::new(config.config);
+        }
+    };
+    let mut source_file = quote.parse();
+    rename::rename_symbol(&mut source_file.modules[0], Namespace::Type, "Config", "Settings");
+    let expected_quote = quote! {
+        struct Settings {
+            config: u32
+        }
+
+        impl Settings {
+            fn new(config: u32) -> Self {
+                Settings { config }
+            }
+        }
+
+        fn build<Config>(value: Config) -> Config {
+            value
+        }
+
+        fn main() {
+            let Config = 1;
+            let config = Settings::new(Config);
+            let other: self::Settings = crate::anonymous::Settings::new(config.config);
+        }
+    };
+    let expected_source_file = expected_quote.parse();
+    assert_eq!(expected_source_file, source_file);
+}
+
+#[rstest]
+fn rename_symbol_in_value_namespace() {
+    let quote = quote! {
+        struct Holder {
+            config: u32
+        }
+
+        fn config() -> u32 {
+            0
+        }
+
+        fn main() {
+            let value = config();
+            let callback = |config: u32| config + 1;
+            match Some(value) {
+                Some(config) => config,
+                None => config(),
+            };
+            let holder = Holder { config: config() };
+            let config = holder.config;
+            println!("{}", config);
+        }
+    };
+    let mut source_file = quote.parse();
+    rename::rename_symbol(&mut source_file.modules[0], Namespace::Value, "config", "settings");
+    let expected_quote = quote! {
+        struct Holder {
+            config: u32
+        }
+
+        fn settings() -> u32 {
+            0
+        }
+
+        fn main() {
+            let value = settings();
+            let callback = |config: u32| config + 1;
+            match Some(value) {
+                Some(config) => config,
+                None => settings(),
+            };
+            let holder = Holder { config: settings() };
+            let config = holder.config;
+            println!("{}", config);
+        }
+    };
+    let expected_source_file = expected_quote.parse();
+    assert_eq!(expected_source_file, source_file);
+}
+
+#[rstest]
+fn rename_symbol_of_tuple_struct() {
+    let quote = quote! {
+        struct Meters(u32);
+
+        fn main() {
+            let Meters(value) = Meters(1);
+        }
+    };
+    let mut source_file = quote.parse();
+    rename::rename_symbol(&mut source_file.modules[0], Namespace::Type, "Meters", "Distance");
+    let expected_quote = quote! {
+        struct Distance(u32);
+
+        fn main() {
+            let Distance(value) = Distance(1);
+        }
+    };
+    let expected_source_file = expected_quote.parse();
+    assert_eq!(expected_source_file, source_file);
+}
+
+#[rstest]
+fn rename_field() {
+    let quote = quote! {
+        struct Config {
+            value: u32
+        }
+
+        struct Other {
+            value: u32
+        }
+
+        impl Config {
+            fn new(value: u32) -> Self {
+                Self { value }
+            }
+
+            fn value(&self) -> u32 {
+                self.value
+            }
+        }
+
+        fn main(other: Other) {
+            let config = Config { value: other.value };
+            let Config { value } = config;
+            let copy: &Config = &config;
+            let value = copy.value + other.value;
+        }
+    };
+    let mut source_file = quote.parse();
+    rename::rename_field(&mut source_file, "Config", "value", "amount");
+    let expected_quote = quote! {
+        struct Config {
+            amount: u32
+        }
+
+        struct Other {
+            value: u32
+        }
+
+        impl Config {
+            fn new(value: u32) -> Self {
+                Self { amount: value }
+            }
+
+            fn value(&self) -> u32 {
+                self.amount
+            }
+        }
+
+        fn main(other: Other) {
+            let config = Config { amount: other.value };
+            let Config { amount: value } = config;
+            let copy: &Config = &config;
+            let value = copy.amount + other.value;
+        }
+    };
+    let expected_source_file = expected_quote.parse();
+    assert_eq!(expected_source_file, source_file);
+}
+
+#[rstest]
+fn rename_variant() {
+    let quote = quote! {
+        enum Shape {
+            Circle { radius: u32 },
+            Square(u32),
+            Empty,
+        }
+
+        enum Other {
+            Circle,
+        }
+
+        impl Shape {
+            fn empty() -> Self {
+                Self::Empty
+            }
+
+            fn size(&self) -> u32 {
+                match self {
+                    Self::Circle { radius } => *radius,
+                    Shape::Square(side) => *side,
+                    Shape::Empty => 0,
+                }
+            }
+        }
+
+        fn main() {
+            let shape = Shape::Circle { radius: 1 };
+            let other = Other::Circle;
+        }
+    };
+    let mut source_file = quote.parse();
+    rename::rename_variant(&mut source_file, "Shape", "Circle", "Disc");
+    let expected_quote = quote! {
+        enum Shape {
+            Disc { radius: u32 },
+            Square(u32),
+            Empty,
+        }
+
+        enum Other {
+            Circle,
+        }
+
+        impl Shape {
+            fn empty() -> Self {
+                Self::Empty
+            }
+
+            fn size(&self) -> u32 {
+                match self {
+                    Self::Disc { radius } => *radius,
+                    Shape::Square(side) => *side,
+                    Shape::Empty => 0,
+                }
+            }
+        }
+
+        fn main() {
+            let shape = Shape::Disc { radius: 1 };
+            let other = Other::Circle;
+        }
+    };
+    let expected_source_file = expected_quote.parse();
+    assert_eq!(expected_source_file, source_file);
+}
+
+#[rstest]
+fn rename_method() {
+    let quote = quote! {
+        struct Foo;
+
+        struct Bar;
+
+        impl Foo {
+            fn build(&self) -> u32 {
+                0
+            }
+
+            fn rebuild(&self) -> u32 {
+                self.build() + Self::build(self)
+            }
+        }
+
+        impl Bar {
+            fn build(&self) -> u32 {
+                1
+            }
+        }
+
+        fn main(bar: Bar) {
+            let foo: Foo = Foo;
+            let value = foo.build() + Foo::build(&foo) + bar.build();
+            let other = make();
+            other.build();
+        }
+    };
+    let mut source_file = quote.parse();
+    let ambiguous_call_sites = rename::rename_method(&mut source_file, "Foo", "build", "make");
+    let expected_quote = quote! {
+        struct Foo;
+
+        struct Bar;
+
+        impl Foo {
+            fn make(&self) -> u32 {
+                0
+            }
+
+            fn rebuild(&self) -> u32 {
+                self.make() + Self::make(self)
+            }
+        }
+
+        impl Bar {
+            fn build(&self) -> u32 {
+                1
+            }
+        }
+
+        fn main(bar: Bar) {
+            let foo: Foo = Foo;
+            let value = foo.make() + Foo::make(&foo) + bar.build();
+            let other = make();
+            other.build();
+        }
+    };
+    let expected_source_file = expected_quote.parse();
+    assert_eq!(expected_source_file, source_file);
+    assert_eq!(
+        vec![rename::MethodCallSite {
+            module: "anonymous".to_string(),
+            item: Some("main".to_string()),
+            call: quote!(other.build()).to_string(),
+        }],
+        ambiguous_call_sites
+    );
+}
+
+#[rstest]
+fn rename_item_across_modules() {
+    let mut source_file = source_file(vec![
+        ("a", quote! {
+            pub struct Foo;
+
+            pub struct Bar;
+
+            impl Foo {
+                pub fn new() -> Self {
+                    Foo
+                }
+            }
+        }),
+        ("b", quote! {
+            use super::a;
+            use crate::a::{Foo, Bar as Baz};
+
+            fn build(value: Baz) -> Foo {
+                let other: crate::a::Foo = a::Foo::new();
+                Foo::new()
+            }
+        }),
+        ("c", quote! {
+            pub use crate::a::Foo;
+        }),
+        ("d", quote! {
+            use crate::c::Foo;
+            use crate::a::Foo as Alias;
+
+            fn build(value: u32) -> Alias {
+                Alias::new()
+            }
+        }),
+    ]);
+    rename::rename_item(&mut source_file, "a", Namespace::Type, "Foo", "Qux");
+    let expected_source_file = self::source_file(vec![
+        ("a", quote! {
+            pub struct Qux;
+
+            pub struct Bar;
+
+            impl Qux {
+                pub fn new() -> Self {
+                    Qux
+                }
+            }
+        }),
+        ("b", quote! {
+            use super::a;
+            use crate::a::{Qux, Bar as Baz};
+
+            fn build(value: Baz) -> Qux {
+                let other: crate::a::Qux = a::Qux::new();
+                Qux::new()
+            }
+        }),
+        ("c", quote! {
+            pub use crate::a::Qux;
+        }),
+        ("d", quote! {
+            use crate::c::Qux;
+            use crate::a::Qux as Alias;
+
+            fn build(value: u32) -> Alias {
+                Alias::new()
+            }
+        }),
+    ]);
+    assert_eq!(expected_source_file, source_file);
+}
+
+#[rstest]
+fn from_item() {
+    let quote = quote! {
+        struct Foo {
+            bar: Bar,
+        }
+
+        impl Foo {
+            fn new<Bar>(value: Bar) -> Self {
+                let Bar = 1;
+                Self {
+                    bar: self::Bar::default(),
+                }
+            }
+
+            fn bar(&self) -> &Bar {
+                &self.bar
+            }
+        }
+    };
+    let mut source_file = quote.parse();
+    rename::from_item(&mut source_file.modules[0].items[0], &"Bar".to_string(), &"Baz".to_string());
+    let expected_quote = quote! {
+        struct Foo {
+            bar: Baz,
+        }
+
+        impl Foo {
+            fn new<Bar>(value: Bar) -> Self {
+                let Bar = 1;
+                Self {
+                    bar: self::Baz::default(),
+                }
+            }
+
+            fn bar(&self) -> &Baz {
+                &self.bar
+            }
+        }
+    };
+    assert_eq!(expected_quote.parse(), source_file);
+}
+
+#[rstest]
+fn from_item_matching() {
+    let quote = quote! {
+        fn build(model: crate::models::Model, other: crate::other::Model) -> Vec<models::Model> {
+            vec![]
+        }
+    };
+    let mut source_file = quote.parse();
+    let pattern = crate::utils::path_pattern::PathPattern::parse("**::models::Model").unwrap();
+    rename::from_item_matching(&mut source_file.modules[0].items[0], &pattern, "Entity");
+    let expected_quote = quote! {
+        fn build(model: crate::models::Entity, other: crate::other::Model) -> Vec<models::Entity> {
+            vec![]
+        }
+    };
+    assert_eq!(expected_quote.parse(), source_file);
+}
+
+#[rstest]
+fn rename_with_ident_patterns_resolved_through_symbols() {
+    let quote = quote! {
+        struct Foo;
+
+        #[allow(non_upper_case_globals)]
+        const limit: u32 = 1;
+
+        impl Foo {
+            fn build(&self) -> u32 {
+                0
+            }
+        }
+
+        fn main(value: u32) -> u32 {
+            let N: Foo = Foo;
+            match value {
+                limit => N.build(),
+                _ => 0,
+            }
+        }
+    };
+    let mut source_file = quote.parse();
+    let ambiguous_call_sites = rename::rename_method(&mut source_file, "Foo", "build", "make");
+    rename::rename_symbol(&mut source_file.modules[0], Namespace::Value, "limit", "LIMIT");
+    let expected_quote = quote! {
+        struct Foo;
+
+        #[allow(non_upper_case_globals)]
+        const LIMIT: u32 = 1;
+
+        impl Foo {
+            fn make(&self) -> u32 {
+                0
+            }
+        }
+
+        fn main(value: u32) -> u32 {
+            let N: Foo = Foo;
+            match value {
+                LIMIT => N.make(),
+                _ => 0,
+            }
+        }
+    };
+    let expected_source_file = expected_quote.parse();
+    assert_eq!(expected_source_file, source_file);
+    assert!(ambiguous_call_sites.is_empty());
+}
+
+#[rstest]
+fn rename_item_with_ident_patterns_resolved_through_imports() {
+    let mut source_file = source_file(vec![
+        ("a", quote! {
+            #[allow(non_upper_case_globals)]
+            pub const limit: u32 = 1;
+        }),
+        ("b", quote! {
+            use crate::a::limit;
+            use std::f64::consts::PI;
+
+            fn check(value: u32, angle: f64) -> u32 {
+                let PI = angle;
+                match value {
+                    limit => 1,
+                    _ => 0,
+                }
+            }
+        }),
+    ]);
+    rename::rename_item(&mut source_file, "a", Namespace::Value, "limit", "LIMIT");
+    let expected_source_file = self::source_file(vec![
+        ("a", quote! {
+            #[allow(non_upper_case_globals)]
+            pub const LIMIT: u32 = 1;
+        }),
+        ("b", quote! {
+            use crate::a::LIMIT;
+            use std::f64::consts::PI;
+
+            fn check(value: u32, angle: f64) -> u32 {
+                let PI = angle;
+                match value {
+                    LIMIT => 1,
+                    _ => 0,
+                }
+            }
+        }),
+    ]);
+    assert_eq!(expected_source_file, source_file);
+}
@@ -19,6 +19,7 @@ where
 
 pub fn from_item(item: &mut Item, context: &mut Context) {
     item.walk(context);
+    item.write_functions();
 }
 
 pub fn from_type(type_: &mut syn::Type, context: &mut Context) {
@@ -1,7 +1,9 @@
 use crate::items::item::ItemTrait;
 use crate::items::source_file::SourceFile;
 use crate::utils::path::Path;
+use crate::utils::path_pattern::PathPattern;
 use crate::walkers::expr::ExprWalker;
+use crate::walkers::path::PathWalker;
 use crate::walkers::type_::TypeWalker;
 use crate::walkers::Context;
 
@@ -11,18 +13,38 @@ fn create_context(prefix: Path) -> Context {
         expr_predicate: None,
         stmt_predicate: None,
         path_predicate: Some(Box::new(move |value| {
-            if value.segments.len() == 1 {
-                // trimming a path with only one segment has not meaning.
-                // if prefix match then path segments will be emptied.
-                return false;
+            // the last segment is always kept, trimming only happens in generic arguments of a
+            // path with only one segment.
+            match Path::from(&*value).strip_prefix(&prefix) {
+                Some(path) => {
+                    *value = path.to_syn_path();
+                    true
+                }
+                None => false,
             }
-            let mut path = Path::from(value.clone());
-            let had_been_trimmed = path.trim_start(&prefix);
-            if had_been_trimmed == false {
-                return false;
-            }
-            *value = path.to_syn_path();
-            return true;
+        })),
+        ident_predicate: None,
+    }
+}
+
+fn create_pattern_context(pattern: PathPattern) -> Context {
+    Context {
+        type_predicate: None,
+        expr_predicate: None,
+        stmt_predicate: None,
+        path_predicate: Some(Box::new(move |value| {
+            PathWalker::for_each_argument_type(value, |type_| from_type_matching(type_, &pattern));
+            let path = Path::from(&*value);
+            let count = match pattern.match_prefix(&path) {
+                Some(value) => value,
+                None => return false,
+            };
+            value.leading_colon = None;
+            value.segments = path.iter()
+                .skip(count)
+                .cloned()
+                .collect();
+            true
         })),
         ident_predicate: None,
     }
@@ -33,6 +55,7 @@ pub fn from_source_file(source_file: &mut SourceFile, prefix: &Path) {
     for module in source_file.modules.iter_mut() {
         for item in module.items.iter_mut() {
             item.walk(&mut context);
+            item.write_functions();
         }
     }
 }
@@ -45,4 +68,26 @@ pub fn from_type(type_: &mut syn::Type, prefix: &Path) {
 pub fn from_expr(expr: &mut syn::Expr, prefix: &Path) {
     let mut context = create_context(prefix.clone());
     ExprWalker::walk(expr, &mut context)
-}
\ No newline at end of file
+}
+
+/// Trim the longest prefix matched by `pattern` from the paths of a source file, e.g. `std::*`
+/// trims `std::collections::HashMap` to `HashMap`. The last segment of a path is always kept.
+pub fn from_source_file_matching(source_file: &mut SourceFile, pattern: &PathPattern) {
+    let mut context = create_pattern_context(pattern.clone());
+    for module in source_file.modules.iter_mut() {
+        for item in module.items.iter_mut() {
+            item.walk(&mut context);
+            item.write_functions();
+        }
+    }
+}
+
+pub fn from_type_matching(type_: &mut syn::Type, pattern: &PathPattern) {
+    let mut context = create_pattern_context(pattern.clone());
+    TypeWalker::walk(type_, &mut context)
+}
+
+pub fn from_expr_matching(expr: &mut syn::Expr, pattern: &PathPattern) {
+    let mut context = create_pattern_context(pattern.clone());
+    ExprWalker::walk(expr, &mut context)
+}
@@ -1,5 +1,7 @@
+use crate::functions::trim_path;
 use crate::utils::parsing::TokenStreamExt;
 use crate::utils::path::Path;
+use crate::utils::path_pattern::PathPattern;
 use pretty_assertions::assert_eq;
 use quote::quote;
 use rstest::rstest;
@@ -23,7 +25,7 @@ fn from_source_file() {
     };
     let path_prefix = Path::new("std").join("path").to_owned();
     let mut source_file = quote.parse();
-    crate::functions::trim_path::from_source_file(
+    trim_path::from_source_file(
         &mut source_file,
         &path_prefix,
     );
@@ -65,7 +67,7 @@ fn with_one_segment() {
     };
     let path_prefix = Path::new("PathBuf");
     let mut source_file = quote.parse();
-    crate::functions::trim_path::from_source_file(
+    trim_path::from_source_file(
         &mut source_file,
         &path_prefix,
     );
@@ -86,4 +88,43 @@ fn with_one_segment() {
     };
     let expected_source_file = expected_quote.parse();
     assert_eq!(expected_source_file, source_file);
-}
\ No newline at end of file
+}
+
+#[rstest]
+fn from_source_file_matching() {
+    let quote = quote! {
+        struct MyStruct {
+            paths: std::collections::HashMap<String, std::path::PathBuf>,
+            model: crate::models::Model
+        }
+
+        impl MyStruct {
+            fn new() -> Self {
+                Self {
+                    paths: std::collections::HashMap::new(),
+                    model: crate::models::Model::default()
+                }
+            }
+        }
+    };
+    let pattern = PathPattern::parse("std::*").unwrap();
+    let mut source_file = quote.parse();
+    trim_path::from_source_file_matching(&mut source_file, &pattern);
+    let expected_quote = quote! {
+        struct MyStruct {
+            paths: HashMap<String, PathBuf>,
+            model: crate::models::Model
+        }
+
+        impl MyStruct {
+            fn new() -> Self {
+                Self {
+                    paths: HashMap::new(),
+                    model: crate::models::Model::default()
+                }
+            }
+        }
+    };
+    let expected_source_file = expected_quote.parse();
+    assert_eq!(expected_source_file, source_file);
+}
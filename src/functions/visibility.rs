@@ -0,0 +1,234 @@
+use crate::items::item::{Item, ItemTrait, VisibilityTrait};
+use crate::items::source_file::SourceFile;
+use crate::items::symbol_table::{Namespace, SymbolTable};
+use crate::utils::path::Path;
+use crate::walkers::scope::{Scope, ScopeVisitor, ScopeWalker};
+use proc_macro2::{TokenStream, TokenTree};
+use syn::{ExprMethodCall, Macro, VisRestricted, Visibility};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct VisibilityChange {
+    /// Absolute path of the item (e.g. `crate::module::Foo` or `crate::module::Foo::new`).
+    pub path: Path,
+    pub from: Visibility,
+    pub to: Visibility,
+}
+
+/// A reference found in `module`, either a resolved path or a bare name (method call or
+/// ident of an unparsed macro) that cannot be resolved.
+struct UseSite {
+    module: String,
+    segments: Vec<String>,
+    is_name: bool,
+}
+
+struct UseSiteCollector<'a> {
+    symbol_table: &'a SymbolTable,
+    module: String,
+    use_sites: Vec<UseSite>,
+}
+
+impl UseSiteCollector<'_> {
+    fn push_path(&mut self, path: &Path) {
+        if let Some(path) = self.symbol_table.resolve_item(&self.module, path) {
+            self.use_sites.push(UseSite {
+                module: self.module.clone(),
+                segments: segments_of(&path),
+                is_name: false,
+            });
+        }
+    }
+
+    fn push_name(&mut self, name: String) {
+        self.use_sites.push(UseSite {
+            module: self.module.clone(),
+            segments: vec![name],
+            is_name: true,
+        });
+    }
+
+    fn collect_idents(&mut self, tokens: TokenStream) {
+        for token in tokens {
+            match token {
+                TokenTree::Ident(value) => self.push_name(value.to_string()),
+                TokenTree::Group(value) => self.collect_idents(value.stream()),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl ScopeVisitor for UseSiteCollector<'_> {
+    fn visit_path(&mut self, path: &mut syn::Path, namespace: Namespace, scope: &Scope) {
+        if path.leading_colon.is_some() {
+            return;
+        }
+        let path = scope.resolve_self(path);
+        let ident = match path.segments.first() {
+            Some(value) => value.ident.to_string(),
+            None => return,
+        };
+        let namespace = match path.segments.len() {
+            1 => namespace,
+            _ => Namespace::Type,
+        };
+        if scope.is_local(&ident, namespace) {
+            return;
+        }
+        self.push_path(&Path::from(path));
+    }
+
+    fn visit_method_call(&mut self, _receiver: Option<&syn::Path>, call: &mut ExprMethodCall, _scope: &Scope) {
+        self.push_name(call.method.to_string());
+    }
+
+    fn visit_unparsed_macro(&mut self, macro_: &Macro, _scope: &Scope) {
+        self.collect_idents(macro_.tokens.clone());
+    }
+}
+
+fn segments_of(path: &Path) -> Vec<String> {
+    path.iter()
+        .map(|segment| segment.ident.to_string())
+        .collect()
+}
+
+fn path_of(segments: &[String]) -> Path {
+    let mut path = Path::new(&segments[0]);
+    for segment in segments.iter().skip(1) {
+        path.join(segment);
+    }
+    path
+}
+
+/// 0 for private, 1 for visibilities restricted to the crate or part of it, 2 for `pub`.
+fn rank(visibility: &Visibility) -> u8 {
+    match visibility {
+        Visibility::Public(_) => 2,
+        Visibility::Restricted(value) if value.path.is_ident("self") => 0,
+        Visibility::Restricted(_) => 1,
+        Visibility::Inherited => 0,
+    }
+}
+
+fn crate_visibility() -> Visibility {
+    Visibility::Restricted(VisRestricted {
+        pub_token: Default::default(),
+        paren_token: Default::default(),
+        in_token: None,
+        path: Box::new(Path::new("crate").to_syn_path()),
+    })
+}
+
+struct VisibilityLowerer {
+    use_sites: Vec<UseSite>,
+    reexports: Vec<Vec<String>>,
+    changes: Vec<VisibilityChange>,
+}
+
+impl VisibilityLowerer {
+    fn is_reexported(&self, segments: &[String]) -> bool {
+        self.reexports.iter().any(|reexport| segments.starts_with(reexport))
+    }
+
+    /// Lowest visibility rank the item needs. Methods are also matched by name since the
+    /// receiver type of a method call is not always known.
+    fn required_rank(&self, module: &str, segments: &[String], is_method: bool) -> u8 {
+        let is_used_outside = self.use_sites.iter()
+            .filter(|use_site| use_site.module != module)
+            .any(|use_site| match use_site.is_name {
+                true => is_method && use_site.segments.last() == segments.last(),
+                false => use_site.segments.starts_with(segments),
+            });
+        match is_used_outside {
+            true => 1,
+            false => 0,
+        }
+    }
+
+    fn lower(&mut self, module: &str, segments: Vec<String>, is_method: bool, item: &mut dyn VisibilityTrait) {
+        let visibility = match item.visibility() {
+            Some(value) => value.clone(),
+            None => return,
+        };
+        if self.is_reexported(&segments) {
+            return;
+        }
+        let required_rank = self.required_rank(module, &segments, is_method);
+        if required_rank >= rank(&visibility) {
+            return;
+        }
+        let new_visibility = match required_rank {
+            0 => Visibility::Inherited,
+            _ => crate_visibility(),
+        };
+        item.set_visibility(new_visibility.clone());
+        self.changes.push(VisibilityChange {
+            path: path_of(&segments),
+            from: visibility,
+            to: new_visibility,
+        });
+    }
+}
+
+/// Lower the visibility of the items of a source file to the minimum required by their use
+/// sites: items only used in their own module become private and items used from another module
+/// become `pub(crate)`. Inherent impl functions are lowered the same way. Visibility is never
+/// raised, items re-exported by a `pub use` keep theirs and the items of root modules (`lib`,
+/// `main` and `mod`) are left alone as they form the public API of the crate. Returns the
+/// applied changes.
+pub fn lower_from_source_file(source_file: &mut SourceFile) -> Vec<VisibilityChange> {
+    let symbol_table = source_file.symbol_table();
+    let mut lowerer = VisibilityLowerer {
+        use_sites: Vec::new(),
+        reexports: Vec::new(),
+        changes: Vec::new(),
+    };
+    for module in source_file.modules.iter() {
+        let mut collector = UseSiteCollector {
+            symbol_table: &symbol_table,
+            module: module.file_name.clone(),
+            use_sites: Vec::new(),
+        };
+        ScopeWalker::new(&mut collector).walk_module(&mut module.clone());
+        for use_item in module.use_items.iter() {
+            let is_reexport = !matches!(use_item.0.vis, Visibility::Inherited);
+            for (path, _, _) in use_item.leaves() {
+                if is_reexport {
+                    if let Some(path) = symbol_table.resolve_item(&module.file_name, &path) {
+                        lowerer.reexports.push(segments_of(&path));
+                    }
+                }
+                collector.push_path(&path);
+            }
+        }
+        lowerer.use_sites.append(&mut collector.use_sites);
+    }
+    for module in source_file.modules.iter_mut() {
+        if matches!(module.file_name.as_str(), "lib" | "main" | "mod") {
+            continue;
+        }
+        let module_name = module.file_name.clone();
+        for item in module.items.iter_mut() {
+            let item_segments = vec!["crate".to_string(), module_name.clone(), item.ident()];
+            let impl_items = match item {
+                Item::Struct(value) => &mut value.impl_items,
+                Item::Enum(value) => &mut value.impl_items,
+                _ => {
+                    lowerer.lower(&module_name, item_segments, false, item);
+                    continue;
+                }
+            };
+            for impl_item in impl_items.iter_mut().filter(|impl_item| impl_item.item.trait_.is_none()) {
+                for function in impl_item.functions.iter_mut() {
+                    let mut segments = item_segments.clone();
+                    segments.push(function.ident());
+                    lowerer.lower(&module_name, segments, true, function);
+                }
+                impl_item.write_functions();
+            }
+            lowerer.lower(&module_name, item_segments, false, item);
+        }
+    }
+    lowerer.changes
+}
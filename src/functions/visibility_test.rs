@@ -0,0 +1,114 @@
+use crate::functions::visibility;
+use crate::items::source_file::SourceFile;
+use crate::test_utils::fixtures::source_file;
+use pretty_assertions::assert_eq;
+use quote::quote;
+use rstest::rstest;
+
+fn module_tokens(source_file: &SourceFile, index: usize) -> String {
+    let items = source_file.modules[index].decompose();
+    quote!(#(#items)*).to_string()
+}
+
+#[rstest]
+fn lower_from_source_file() {
+    let mut source_file = source_file(vec![
+        ("lib", quote! {
+            pub use crate::models::Role;
+
+            pub fn build() -> crate::models::Model {
+                crate::models::Model::new()
+            }
+        }),
+        ("models", quote! {
+            pub enum Role {
+                Admin,
+            }
+
+            pub struct Model {
+                pub(super) id: u32,
+            }
+
+            impl Model {
+                pub fn new() -> Self {
+                    Self::with_id(DEFAULT_ID)
+                }
+
+                pub fn with_id(id: u32) -> Self {
+                    Self { id }
+                }
+
+                pub fn id(&self) -> u32 {
+                    self.id
+                }
+            }
+
+            pub const DEFAULT_ID: u32 = 0;
+
+            pub(crate) fn unused() {}
+
+            fn private() {}
+        }),
+        ("services", quote! {
+            use crate::models::Model;
+
+            pub fn identify(model: &Model) -> u32 {
+                model.id()
+            }
+        }),
+    ]);
+    let changes = visibility::lower_from_source_file(&mut source_file);
+    let expected_quote = quote! {
+        pub enum Role {
+            Admin,
+        }
+
+        pub(crate) struct Model {
+            pub(super) id: u32,
+        }
+
+        impl Model {
+            pub(crate) fn new() -> Self {
+                Self::with_id(DEFAULT_ID)
+            }
+
+            fn with_id(id: u32) -> Self {
+                Self { id }
+            }
+
+            pub(crate) fn id(&self) -> u32 {
+                self.id
+            }
+        }
+
+        const DEFAULT_ID: u32 = 0;
+
+        fn unused() {}
+
+        fn private() {}
+    };
+    assert_eq!(expected_quote.to_string(), module_tokens(&source_file, 1));
+    let expected_quote = quote! {
+        use crate::models::Model;
+
+        fn identify(model: &Model) -> u32 {
+            model.id()
+        }
+    };
+    assert_eq!(expected_quote.to_string(), module_tokens(&source_file, 2));
+    let paths = changes.iter()
+        .map(|change| change.path.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            "crate::models::Model::new",
+            "crate::models::Model::with_id",
+            "crate::models::Model::id",
+            "crate::models::Model",
+            "crate::models::DEFAULT_ID",
+            "crate::models::unused",
+            "crate::services::identify",
+        ],
+        paths
+    );
+}
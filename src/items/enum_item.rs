@@ -1,10 +1,10 @@
 use crate::items::implementation_item::ImplementationItem;
-use crate::items::item::ItemTrait;
+use crate::items::item::{ItemTrait, VisibilityTrait};
 use crate::walkers::expr::ExprWalker;
 use crate::walkers::fields::FieldsWalker;
 use crate::walkers::generics::GenericsWalker;
 use crate::walkers::Context;
-use syn::{ItemEnum, ItemImpl};
+use syn::{ItemEnum, ItemImpl, Visibility};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct EnumItem {
@@ -42,4 +42,14 @@ impl ItemTrait for EnumItem {
             impl_item.walk(context);
         }
     }
-}
\ No newline at end of file
+}
+
+impl VisibilityTrait for EnumItem {
+    fn visibility(&self) -> Option<&Visibility> {
+        Some(&self.item.vis)
+    }
+
+    fn set_visibility(&mut self, visibility: Visibility) {
+        self.item.vis = visibility;
+    }
+}
@@ -1,4 +1,4 @@
-use crate::items::item::ItemTrait;
+use crate::items::item::{ItemTrait, VisibilityTrait};
 use crate::walkers::signature::SignatureWalker;
 use crate::walkers::statement::StatementWalker;
 use crate::walkers::Context;
@@ -35,6 +35,13 @@ impl FnType {
         }
     }
 
+    pub fn set_visibility(&mut self, visibility: Visibility) {
+        match self {
+            FnType::Global(value) => value.vis = visibility,
+            FnType::Implementation(value) => value.vis = visibility,
+        }
+    }
+
     pub fn defaultness(&self) -> &Option<Token![default]> {
         match self {
             FnType::Global(_) => panic!("Defaultness not available in global function"),
@@ -119,4 +126,14 @@ impl ItemTrait for FnItem {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+impl VisibilityTrait for FnItem {
+    fn visibility(&self) -> Option<&Visibility> {
+        Some(self.item.visibility())
+    }
+
+    fn set_visibility(&mut self, visibility: Visibility) {
+        self.item.set_visibility(visibility);
+    }
+}
@@ -34,6 +34,20 @@ impl ImplementationItem {
     }
 }
 
+impl ImplementationItem {
+    /// Write `functions` back into the impl block after they have been modified.
+    pub fn write_functions(&mut self) {
+        let mut functions = self.functions.iter();
+        for item in self.item.items.iter_mut() {
+            if let ImplItem::Fn(value) = item {
+                if let Some(FnType::Implementation(function)) = functions.next().map(|function| &function.item) {
+                    *value = function.clone();
+                }
+            }
+        }
+    }
+}
+
 impl ItemTrait for ImplementationItem {
     fn ident(&self) -> String {
         match self.item.self_ty.as_ref() {
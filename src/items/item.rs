@@ -3,12 +3,20 @@ use crate::items::fn_item::FnItem;
 use crate::items::other_item::OtherItem;
 use crate::items::struct_item::StructItem;
 use crate::walkers::Context;
+use syn::Visibility;
 
 pub trait ItemTrait {
     fn ident(&self) -> String;
     fn walk(&mut self, context: &mut Context);
 }
 
+/// Visibility of an item. Items that cannot have one (e.g. macro invocations or foreign modules)
+/// return `None` and ignore `set_visibility`.
+pub trait VisibilityTrait {
+    fn visibility(&self) -> Option<&Visibility>;
+    fn set_visibility(&mut self, visibility: Visibility);
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Item {
     Struct(StructItem),
@@ -27,6 +35,22 @@ impl Item {
         }
     }
 
+    /// Ident of the item, or `None` for items without one (e.g. macro invocations).
+    pub fn try_ident(&self) -> Option<String> {
+        match self {
+            Item::Other(value) => {
+                match &value.item {
+                    syn::Item::Macro(value) => value.ident.as_ref().map(|ident| ident.to_string()),
+                    syn::Item::Const(_) | syn::Item::Static(_) | syn::Item::Trait(_) | syn::Item::TraitAlias(_)
+                    | syn::Item::Type(_) | syn::Item::Union(_) | syn::Item::Mod(_) => Some(self.ident()),
+                    syn::Item::Fn(value) => Some(value.sig.ident.to_string()),
+                    _ => None,
+                }
+            }
+            _ => Some(self.ident()),
+        }
+    }
+
     pub fn as_struct_ref(&self) -> Option<&StructItem> {
         match self {
             Item::Struct(value) => Some(value),
@@ -40,6 +64,18 @@ impl Item {
             _ => None
         }
     }
+
+    /// Write the functions of the impl blocks back into them after a walk modified them.
+    pub fn write_functions(&mut self) {
+        let impl_items = match self {
+            Item::Struct(value) => &mut value.impl_items,
+            Item::Enum(value) => &mut value.impl_items,
+            _ => return,
+        };
+        for impl_item in impl_items.iter_mut() {
+            impl_item.write_functions();
+        }
+    }
 }
 
 impl ItemTrait for Item {
@@ -60,4 +96,24 @@ impl ItemTrait for Item {
             Item::Other(value) => value.walk(context),
         }
     }
-}
\ No newline at end of file
+}
+
+impl VisibilityTrait for Item {
+    fn visibility(&self) -> Option<&Visibility> {
+        match self {
+            Item::Struct(value) => value.visibility(),
+            Item::Enum(value) => value.visibility(),
+            Item::Fn(value) => VisibilityTrait::visibility(value),
+            Item::Other(value) => value.visibility(),
+        }
+    }
+
+    fn set_visibility(&mut self, visibility: Visibility) {
+        match self {
+            Item::Struct(value) => value.set_visibility(visibility),
+            Item::Enum(value) => value.set_visibility(visibility),
+            Item::Fn(value) => VisibilityTrait::set_visibility(value, visibility),
+            Item::Other(value) => value.set_visibility(visibility),
+        }
+    }
+}
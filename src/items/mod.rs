@@ -2,9 +2,23 @@ pub mod enum_item;
 pub mod fn_item;
 pub mod implementation_item;
 pub mod module_item;
+#[cfg(test)]
+#[path = "./module_item_test.rs"]
+mod module_item_test;
 pub mod other_item;
 pub mod source_file;
+#[cfg(test)]
+#[path = "./source_file_test.rs"]
+mod source_file_test;
 pub mod struct_item;
+pub mod symbol_table;
+#[cfg(test)]
+#[path = "./symbol_table_test.rs"]
+mod symbol_table_test;
+
 pub mod use_item;
+#[cfg(test)]
+#[path = "./use_item_test.rs"]
+mod use_item_test;
 pub mod item;
 pub mod macro_;
\ No newline at end of file
@@ -6,9 +6,209 @@ use crate::items::other_item::OtherItem;
 use crate::items::struct_item::StructItem;
 use crate::items::use_item::UseItem;
 use crate::utils::name_conventions::NamingConventions;
+use crate::items::symbol_table::{ModuleSymbols, Namespace, SymbolKind};
 use crate::utils::path::Path;
+use crate::utils::path_pattern::PathPattern;
+use crate::utils::{create_ident, create_use_as_glob};
+use crate::walkers::scope::{Scope, ScopeVisitor, ScopeWalker};
 use crate::walkers::Context;
-use syn::{ItemExternCrate, ItemImpl, ItemUse, Type};
+use crate::debug;
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use quote::ToTokens;
+use std::collections::{HashMap, HashSet};
+use syn::{ExprMethodCall, Fields, Ident, ImplItem, ItemExternCrate, ItemImpl, ItemMod, ItemUse, PathSegment, Type, VisRestricted, Visibility};
+
+struct NestedPathRewriter<'a> {
+    module: &'a str,
+    idents: &'a [String],
+}
+
+/// Import reported by [`ModuleItem::remove_unused_imports`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct UnusedImport {
+    pub ident: String,
+    pub path: Path,
+}
+
+/// Result of [`ModuleItem::remove_unused_imports`].
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct UnusedImports {
+    /// Imports removed, or that would be removed on a dry run.
+    pub unused: Vec<UnusedImport>,
+    /// Imports only possibly used, as traits of unknown kind whose methods the module may call.
+    /// They are kept.
+    pub possible_traits: Vec<UnusedImport>,
+}
+
+/// Macros calling methods of traits which must be in scope, e.g. `write!` and `std::fmt::Write`.
+const TRAIT_METHOD_MACROS: [&str; 2] = ["write", "writeln"];
+
+/// Traits of `std`, `core` and `alloc` (without the crate root) whose methods are commonly
+/// called and which are not in the prelude.
+const STD_TRAITS: [&str; 34] = [
+    "any::Any",
+    "borrow::Borrow",
+    "borrow::BorrowMut",
+    "error::Error",
+    "fmt::Debug",
+    "fmt::Display",
+    "fmt::Write",
+    "future::Future",
+    "future::IntoFuture",
+    "hash::BuildHasher",
+    "hash::Hash",
+    "hash::Hasher",
+    "io::BufRead",
+    "io::Read",
+    "io::Seek",
+    "io::Write",
+    "iter::DoubleEndedIterator",
+    "iter::ExactSizeIterator",
+    "iter::FromIterator",
+    "iter::Product",
+    "iter::Sum",
+    "ops::Deref",
+    "ops::DerefMut",
+    "ops::Index",
+    "ops::IndexMut",
+    "os::unix::ffi::OsStrExt",
+    "os::unix::ffi::OsStringExt",
+    "os::unix::fs::MetadataExt",
+    "os::unix::fs::PermissionsExt",
+    "os::unix::io::AsRawFd",
+    "os::unix::process::CommandExt",
+    "os::windows::ffi::OsStrExt",
+    "str::FromStr",
+    "string::ToString",
+];
+
+/// Whether an import is a trait, as far as the module alone tells.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum ImportKind {
+    Trait,
+    NotTrait,
+    Unknown,
+}
+
+/// Collects the idents a module may refer to an import with: first segment of paths that are
+/// not shadowed by a local binding, and any ident found in attributes or unparsed macros.
+#[derive(Default)]
+struct ReferenceCollector {
+    idents: HashSet<String>,
+    /// Whether the module calls methods, which may come from imported traits.
+    has_method_calls: bool,
+}
+
+impl ReferenceCollector {
+    fn collect_idents(&mut self, tokens: TokenStream) {
+        for token in tokens {
+            match token {
+                TokenTree::Ident(value) => {
+                    self.idents.insert(value.to_string());
+                }
+                TokenTree::Group(value) => self.collect_idents(value.stream()),
+                _ => {}
+            }
+        }
+    }
+
+    fn collect_attribute_idents(&mut self, tokens: TokenStream) {
+        let mut is_attribute = false;
+        for token in tokens {
+            match token {
+                TokenTree::Punct(value) => is_attribute = value.as_char() == '#',
+                TokenTree::Group(value) if is_attribute && value.delimiter() == Delimiter::Bracket => {
+                    self.collect_idents(value.stream());
+                    is_attribute = false;
+                }
+                TokenTree::Group(value) => {
+                    self.collect_attribute_idents(value.stream());
+                    is_attribute = false;
+                }
+                TokenTree::Ident(_) => is_attribute = false,
+                TokenTree::Literal(_) => is_attribute = false,
+            }
+        }
+    }
+}
+
+impl ScopeVisitor for ReferenceCollector {
+    fn visit_path(&mut self, path: &mut syn::Path, namespace: Namespace, scope: &Scope) {
+        if path.leading_colon.is_some() {
+            return;
+        }
+        let ident = match path.segments.first() {
+            Some(value) => value.ident.to_string(),
+            None => return,
+        };
+        let namespace = match path.segments.len() {
+            1 => namespace,
+            _ => Namespace::Type,
+        };
+        if !scope.is_local(&ident, namespace) {
+            self.idents.insert(ident);
+        }
+    }
+
+    /// An ident pattern may match an imported constant or variant the walker could not
+    /// resolve, so it keeps the import of the same name.
+    fn visit_binding(&mut self, ident: &mut Ident, _scope: &Scope) {
+        self.idents.insert(ident.to_string());
+    }
+
+    fn visit_method_call(&mut self, _receiver: Option<&syn::Path>, _call: &mut ExprMethodCall, _scope: &Scope) {
+        self.has_method_calls = true;
+    }
+
+    fn visit_unparsed_macro(&mut self, macro_: &syn::Macro, _scope: &Scope) {
+        let is_trait_method_macro = macro_.path.segments.last()
+            .map(|segment| TRAIT_METHOD_MACROS.contains(&segment.ident.to_string().as_str()))
+            .unwrap_or(false);
+        self.has_method_calls |= is_trait_method_macro;
+        self.collect_idents(macro_.tokens.clone());
+    }
+}
+
+struct PathRootRenamer<'a> {
+    from: &'a str,
+    to: &'a str,
+}
+
+impl ScopeVisitor for PathRootRenamer<'_> {
+    fn visit_path(&mut self, path: &mut syn::Path, _namespace: Namespace, _scope: &Scope) {
+        if path.leading_colon.is_some() {
+            return;
+        }
+        if let Some(segment) = path.segments.first_mut() {
+            if segment.ident == self.from {
+                segment.ident = create_ident(self.to);
+            }
+        }
+    }
+}
+
+impl ScopeVisitor for NestedPathRewriter<'_> {
+    fn visit_path(&mut self, path: &mut syn::Path, namespace: Namespace, scope: &Scope) {
+        if path.leading_colon.is_some() {
+            return;
+        }
+        let ident = match path.segments.first() {
+            Some(value) => value.ident.to_string(),
+            None => return,
+        };
+        if !self.idents.contains(&ident) {
+            return;
+        }
+        let namespace = match path.segments.len() {
+            1 => namespace,
+            _ => Namespace::Type,
+        };
+        if scope.is_local(&ident, namespace) {
+            return;
+        }
+        path.segments.insert(0, PathSegment::from(create_ident(self.module)));
+    }
+}
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ModuleItem {
@@ -131,6 +331,18 @@ impl ModuleItem {
             .collect()
     }
 
+    /// Absolute path of an item of the module, e.g. `crate::models::Model`.
+    pub fn item_path(&self, item: &Item) -> Path {
+        let mut path = Path::new("crate");
+        path.join(&self.file_name).join(item.ident());
+        path
+    }
+
+    /// Items whose absolute path (see [`ModuleItem::item_path`]) matches `pattern`.
+    pub fn find_items_matching(&self, pattern: &PathPattern) -> Vec<&Item> {
+        self.find_items_by(|item| pattern.matches(&self.item_path(item)))
+    }
+
     pub fn find_item_by(&self, mut filter: impl FnMut(&&Item) -> bool) -> Option<&Item> {
         self.items.iter()
             .find(|item| filter(item))
@@ -166,44 +378,46 @@ impl ModuleItem {
 
     fn decompose_items(&self) -> Vec<syn::Item> {
         self.items.iter()
-            .flat_map(|item| {
-                match item {
-                    Item::Struct(value) => {
-                        let mut impl_items = value.impl_items.iter()
-                            .map(|impl_item| syn::Item::Impl(impl_item.item.clone()))
-                            .collect::<Vec<_>>();
-                        let mut items = Vec::new();
-                        items.push(syn::Item::Struct(value.item.clone()));
-                        items.append(&mut impl_items);
-                        items
-                    }
-                    Item::Enum(value) => {
-                        let mut impl_items = value.impl_items.iter()
-                            .map(|impl_item| syn::Item::Impl(impl_item.item.clone()))
-                            .collect::<Vec<_>>();
-                        let mut items = Vec::new();
-                        items.push(syn::Item::Enum(value.item.clone()));
-                        items.append(&mut impl_items);
-                        items
-                    }
-                    Item::Fn(value) => {
-                        let mut items = Vec::new();
-                        match &value.item {
-                            FnType::Global(value) => items.push(syn::Item::Fn(value.clone())),
-                            FnType::Implementation(_) => panic!("Unexpected implementation function"),
-                        };
-                        items
-                    }
-                    Item::Other(value) => {
-                        let mut items = Vec::new();
-                        items.push(value.item.clone());
-                        items
-                    }
-                }
-            })
+            .flat_map(Self::decompose_item)
             .collect()
     }
 
+    fn decompose_item(item: &Item) -> Vec<syn::Item> {
+        match item {
+            Item::Struct(value) => {
+                let mut impl_items = value.impl_items.iter()
+                    .map(|impl_item| syn::Item::Impl(impl_item.item.clone()))
+                    .collect::<Vec<_>>();
+                let mut items = Vec::new();
+                items.push(syn::Item::Struct(value.item.clone()));
+                items.append(&mut impl_items);
+                items
+            }
+            Item::Enum(value) => {
+                let mut impl_items = value.impl_items.iter()
+                    .map(|impl_item| syn::Item::Impl(impl_item.item.clone()))
+                    .collect::<Vec<_>>();
+                let mut items = Vec::new();
+                items.push(syn::Item::Enum(value.item.clone()));
+                items.append(&mut impl_items);
+                items
+            }
+            Item::Fn(value) => {
+                let mut items = Vec::new();
+                match &value.item {
+                    FnType::Global(value) => items.push(syn::Item::Fn(value.clone())),
+                    FnType::Implementation(_) => panic!("Unexpected implementation function"),
+                };
+                items
+            }
+            Item::Other(value) => {
+                let mut items = Vec::new();
+                items.push(value.item.clone());
+                items
+            }
+        }
+    }
+
     pub fn decompose(&self) -> Vec<syn::Item> {
         let mut items = Vec::new();
         items.append(&mut self.decompose_extern_crate_items());
@@ -212,13 +426,218 @@ impl ModuleItem {
         items
     }
 
-    pub fn remove_use_items_starting_with(&mut self, path_prefix: &Path) {
-        self.use_items.retain_mut(|item| {
-            match item.start_with(path_prefix) {
-                true => false,
-                false => true,
+    fn super_visibility() -> Visibility {
+        Visibility::Restricted(VisRestricted {
+            pub_token: Default::default(),
+            paren_token: Default::default(),
+            in_token: None,
+            path: Box::new(Path::new("super").to_syn_path()),
+        })
+    }
+
+    fn raise_visibility(visibility: &mut Visibility) {
+        if let Visibility::Inherited = visibility {
+            *visibility = Self::super_visibility();
+        }
+    }
+
+    fn raise_item_visibility(item: &mut syn::Item) {
+        match item {
+            syn::Item::Struct(value) => {
+                Self::raise_visibility(&mut value.vis);
+                if let Fields::Named(ref mut fields) = value.fields {
+                    for field in fields.named.iter_mut() {
+                        Self::raise_visibility(&mut field.vis);
+                    }
+                }
+            }
+            syn::Item::Enum(value) => Self::raise_visibility(&mut value.vis),
+            syn::Item::Fn(value) => Self::raise_visibility(&mut value.vis),
+            syn::Item::Const(value) => Self::raise_visibility(&mut value.vis),
+            syn::Item::Static(value) => Self::raise_visibility(&mut value.vis),
+            syn::Item::Trait(value) => Self::raise_visibility(&mut value.vis),
+            syn::Item::Type(value) => Self::raise_visibility(&mut value.vis),
+            syn::Item::Union(value) => Self::raise_visibility(&mut value.vis),
+            syn::Item::Mod(value) => Self::raise_visibility(&mut value.vis),
+            syn::Item::Impl(value) if value.trait_.is_none() => {
+                for impl_item in value.items.iter_mut() {
+                    match impl_item {
+                        ImplItem::Fn(value) => Self::raise_visibility(&mut value.vis),
+                        ImplItem::Const(value) => Self::raise_visibility(&mut value.vis),
+                        ImplItem::Type(value) => Self::raise_visibility(&mut value.vis),
+                        _ => {}
+                    }
+                }
             }
+            _ => {}
+        }
+    }
+
+    /// Move the items named by `idents` into an inline submodule named after this module, with
+    /// their private visibility raised to `pub(super)`, and qualify the remaining references.
+    pub fn nest_items(&mut self, idents: &[String]) {
+        // patterns of the remaining items may refer to the nested ones
+        let module = self.clone();
+        let items = self.take_items_by(|item| {
+            item.try_ident()
+                .map(|ident| idents.contains(&ident))
+                .unwrap_or(false)
         });
+        if items.is_empty() {
+            return;
+        }
+        let mut rewriter = NestedPathRewriter {
+            module: &self.file_name,
+            idents,
+        };
+        let mut walker = ScopeWalker::new(&mut rewriter);
+        walker.enter_module(&module);
+        for item in self.items.iter_mut() {
+            walker.walk_item(item);
+        }
+        let mut content = vec![syn::Item::Use(create_use_as_glob(&Path::new("super")))];
+        for item in items.iter() {
+            for mut item in Self::decompose_item(item) {
+                Self::raise_item_visibility(&mut item);
+                content.push(item);
+            }
+        }
+        self.push_item(syn::Item::Mod(ItemMod {
+            attrs: vec![],
+            vis: Visibility::Public(Default::default()),
+            unsafety: None,
+            mod_token: Default::default(),
+            ident: create_ident(&self.file_name),
+            content: Some((Default::default(), content)),
+            semi: None,
+        }));
+    }
+
+    /// Rename the first segment of every path of the module, use items included, e.g. `crate` into
+    /// `super` when the module is moved one level down.
+    pub fn rename_path_root(&mut self, from: &str, to: &str) {
+        let mut renamer = PathRootRenamer {
+            from,
+            to,
+        };
+        ScopeWalker::new(&mut renamer).walk_module(self);
+        for use_item in self.use_items.iter_mut() {
+            use_item.rename_root(from, to);
+        }
+    }
+
+    /// Remove the imports never referenced by the items of the module, including single members
+    /// and renames of use groups. Public use items (re-exports), globs and `as _` imports are
+    /// kept. Traits imported only to call their methods cannot be told apart from unused
+    /// imports: when the module calls methods, unreferenced imports of known traits are kept, and
+    /// so are the ones of unknown kind, reported as possible traits. `dry_run` reports the
+    /// imports without removing them.
+    pub fn remove_unused_imports(&mut self, dry_run: bool) -> UnusedImports {
+        let mut collector = ReferenceCollector::default();
+        let mut items = self.items.clone();
+        let mut walker = ScopeWalker::new(&mut collector);
+        walker.enter_module(self);
+        for item in items.iter_mut() {
+            walker.walk_item(item);
+        }
+        let tokens = self.decompose_items().iter()
+            .map(|item| item.to_token_stream())
+            .collect::<TokenStream>();
+        collector.collect_attribute_idents(tokens);
+        // Imports relative to another import (e.g. `use fmt::Debug`) keep it only when used.
+        let leaves = self.use_items.iter()
+            .flat_map(|use_item| {
+                let is_public = !matches!(use_item.0.vis, Visibility::Inherited);
+                use_item.leaves().into_iter()
+                    .map(move |(path, rename, is_glob)| (path, rename, is_glob || is_public))
+            })
+            .collect::<Vec<_>>();
+        loop {
+            let mut is_changed = false;
+            for (path, rename, is_kept) in leaves.iter() {
+                let ident = Self::bound_ident(path, rename);
+                let is_used = *is_kept || ident == "_" || collector.idents.contains(&ident);
+                if is_used && path.len() > 1 {
+                    is_changed |= collector.idents.insert(path.iter().next().unwrap().ident.to_string());
+                }
+            }
+            if !is_changed {
+                break;
+            }
+        }
+        let symbols = ModuleSymbols::new(self);
+        let import_kinds = leaves.iter()
+            .map(|(path, rename, _)| (Self::bound_ident(path, rename), Self::import_kind(&symbols, path)))
+            .collect::<HashMap<_, _>>();
+        let has_method_calls = collector.has_method_calls;
+        let mut possible_traits = HashSet::new();
+        let mut use_items = self.use_items.clone();
+        let mut unused_imports = UnusedImports::default();
+        for use_item in use_items.iter_mut() {
+            if !matches!(use_item.0.vis, Visibility::Inherited) {
+                continue;
+            }
+            let removed_imports = use_item.retain_imports(|ident| {
+                if collector.idents.contains(ident) {
+                    return true;
+                }
+                match (has_method_calls, import_kinds.get(ident)) {
+                    (true, Some(ImportKind::Trait)) => true,
+                    (true, Some(ImportKind::Unknown)) => {
+                        possible_traits.insert(ident.to_string());
+                        true
+                    }
+                    _ => false,
+                }
+            });
+            unused_imports.unused.extend(removed_imports.into_iter()
+                .map(|(ident, path)| UnusedImport { ident, path }));
+        }
+        unused_imports.possible_traits = leaves.into_iter()
+            .map(|(path, rename, _)| UnusedImport { ident: Self::bound_ident(&path, &rename), path })
+            .filter(|import| possible_traits.contains(&import.ident))
+            .collect();
+        if !dry_run {
+            for unused_import in unused_imports.unused.iter() {
+                debug!("Removing unused import '{}' from module '{}'", unused_import.path, self.file_name);
+            }
+            use_items.retain(|use_item| !use_item.is_empty());
+            self.use_items = use_items;
+        }
+        unused_imports
+    }
+
+    fn bound_ident(path: &Path, rename: &Option<String>) -> String {
+        match rename {
+            Some(value) => value.clone(),
+            None => path.last().unwrap().ident.to_string(),
+        }
+    }
+
+    /// Whether the import of `path` is a trait: a trait of `std` listed in [`STD_TRAITS`] or one
+    /// defined in this module. Imports of other crates and modules are of unknown kind.
+    fn import_kind(symbols: &ModuleSymbols, path: &Path) -> ImportKind {
+        let segments = path.iter()
+            .map(|segment| segment.ident.to_string())
+            .collect::<Vec<_>>();
+        let ident = segments.last().unwrap();
+        let is_local_trait = symbols.symbols().iter()
+            .any(|symbol| symbol.ident == *ident && symbol.kind == SymbolKind::Trait);
+        if is_local_trait {
+            return ImportKind::Trait;
+        }
+        match ["std", "core", "alloc"].contains(&segments[0].as_str()) {
+            true if STD_TRAITS.contains(&segments[1..].join("::").as_str()) => ImportKind::Trait,
+            true => ImportKind::NotTrait,
+            false => ImportKind::Unknown,
+        }
+    }
+
+    pub fn remove_use_items_starting_with(&mut self, path_prefix: &Path) {
+        for use_item in self.use_items.iter_mut() {
+            use_item.remove_leaves_starting_with(path_prefix);
+        }
+        self.use_items.retain(|use_item| !use_item.is_empty());
     }
 }
 
@@ -232,7 +651,7 @@ impl ItemTrait for ModuleItem {
             item.walk(context);
         }
         for use_item in self.use_items.iter_mut() {
-            todo!()
+            use_item.walk(context);
         }
         for extern_crate_item in self.extern_crate_items.iter_mut() {
             context.predict_ident(&mut extern_crate_item.ident);
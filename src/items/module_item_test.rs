@@ -0,0 +1,218 @@
+use crate::items::item::ItemTrait;
+use crate::items::module_item::{UnusedImport, UnusedImports};
+use crate::utils::parsing::TokenStreamExt;
+use crate::utils::path::Path;
+use pretty_assertions::assert_eq;
+use quote::quote;
+use rstest::rstest;
+
+#[rstest]
+#[case::apply(false)]
+#[case::dry_run(true)]
+fn remove_unused_imports(#[case] dry_run: bool) {
+    let quote = quote! {
+        use std::collections::{HashMap, HashSet, BTreeMap as Map};
+        use std::fmt::{self, Display};
+        use std::io::*;
+        use std::sync::Arc;
+        use serde::{Serialize, Deserialize};
+        use itertools::Itertools as _;
+        pub use crate::models::Model;
+
+        #[derive(Serialize)]
+        struct Foo {
+            values: HashMap<String, u32>,
+        }
+
+        fn build(value: u32) -> Map<u32, u32> {
+            let shared = value;
+            let formatted = format!("{}", fmt::format(format_args!("{}", shared)));
+            Map::new()
+        }
+    };
+    let mut source_file = quote.parse();
+    let unused_imports = source_file.modules[0].remove_unused_imports(dry_run);
+    let expected_quote = match dry_run {
+        true => quote,
+        false => quote! {
+            use std::collections::{BTreeMap as Map, HashMap};
+            use std::fmt;
+            use std::io::*;
+            use serde::Serialize;
+            use itertools::Itertools as _;
+            pub use crate::models::Model;
+
+            #[derive(Serialize)]
+            struct Foo {
+                values: HashMap<String, u32>,
+            }
+
+            fn build(value: u32) -> Map<u32, u32> {
+                let shared = value;
+                let formatted = format!("{}", fmt::format(format_args!("{}", shared)));
+                Map::new()
+            }
+        },
+    };
+    let expected_source_file = expected_quote.parse();
+    assert_eq!(expected_source_file, source_file);
+    assert_eq!(
+        vec![
+            UnusedImport {
+                ident: "HashSet".to_string(),
+                path: Path::new("std").join("collections").join("HashSet").to_owned(),
+            },
+            UnusedImport {
+                ident: "Display".to_string(),
+                path: Path::new("std").join("fmt").join("Display").to_owned(),
+            },
+            UnusedImport {
+                ident: "Arc".to_string(),
+                path: Path::new("std").join("sync").join("Arc").to_owned(),
+            },
+            UnusedImport {
+                ident: "Deserialize".to_string(),
+                path: Path::new("serde").join("Deserialize").to_owned(),
+            },
+        ],
+        unused_imports.unused
+    );
+}
+
+#[rstest]
+fn remove_unused_imports_keeps_possible_traits() {
+    let quote = quote! {
+        use std::io::Write;
+        use std::fmt::Write as FmtWrite;
+        use std::fs;
+
+        fn f(w: &mut Vec<u8>) {
+            w.write_all(b"x").unwrap();
+        }
+    };
+    let mut source_file = quote.parse();
+    let unused_imports = source_file.modules[0].remove_unused_imports(false);
+    let expected_quote = quote! {
+        use std::io::Write;
+        use std::fmt::Write as FmtWrite;
+
+        fn f(w: &mut Vec<u8>) {
+            w.write_all(b"x").unwrap();
+        }
+    };
+    let expected_source_file = expected_quote.parse();
+    assert_eq!(expected_source_file, source_file);
+    assert_eq!(
+        vec![
+            UnusedImport {
+                ident: "fs".to_string(),
+                path: Path::new("std").join("fs").to_owned(),
+            },
+        ],
+        unused_imports.unused
+    );
+}
+
+#[rstest]
+fn remove_unused_imports_with_method_calls() {
+    let quote = quote! {
+        use std::collections::HashMap;
+        use std::sync::Arc;
+        use serde::Serialize;
+
+        fn f(values: Vec<u32>) -> usize {
+            values.len()
+        }
+    };
+    let mut source_file = quote.parse();
+    let unused_imports = source_file.modules[0].remove_unused_imports(false);
+    let expected_quote = quote! {
+        use serde::Serialize;
+
+        fn f(values: Vec<u32>) -> usize {
+            values.len()
+        }
+    };
+    let expected_source_file = expected_quote.parse();
+    assert_eq!(expected_source_file, source_file);
+    assert_eq!(
+        UnusedImports {
+            unused: vec![
+                UnusedImport {
+                    ident: "HashMap".to_string(),
+                    path: Path::new("std").join("collections").join("HashMap").to_owned(),
+                },
+                UnusedImport {
+                    ident: "Arc".to_string(),
+                    path: Path::new("std").join("sync").join("Arc").to_owned(),
+                },
+            ],
+            possible_traits: vec![
+                UnusedImport {
+                    ident: "Serialize".to_string(),
+                    path: Path::new("serde").join("Serialize").to_owned(),
+                },
+            ],
+        },
+        unused_imports
+    );
+}
+
+#[rstest]
+fn remove_unused_imports_relative_to_unused_import() {
+    let quote = quote! {
+        use std::fmt;
+        use fmt::Debug;
+        use std::collections;
+        use collections::HashMap;
+
+        fn f() -> HashMap<u32, u32> {
+            HashMap::new()
+        }
+    };
+    let mut source_file = quote.parse();
+    let unused_imports = source_file.modules[0].remove_unused_imports(false);
+    let expected_quote = quote! {
+        use std::collections;
+        use collections::HashMap;
+
+        fn f() -> HashMap<u32, u32> {
+            HashMap::new()
+        }
+    };
+    let expected_source_file = expected_quote.parse();
+    assert_eq!(expected_source_file, source_file);
+    assert_eq!(
+        vec![
+            UnusedImport {
+                ident: "fmt".to_string(),
+                path: Path::new("std").join("fmt").to_owned(),
+            },
+            UnusedImport {
+                ident: "Debug".to_string(),
+                path: Path::new("fmt").join("Debug").to_owned(),
+            },
+        ],
+        unused_imports.unused
+    );
+}
+
+#[rstest]
+fn find_items_matching() {
+    let quote = quote! {
+        struct Model;
+
+        enum Role {}
+
+        fn model() {}
+    };
+    let mut module = quote.parse().modules.remove(0);
+    module.file_name = "models".to_string();
+    let pattern = crate::utils::path_pattern::PathPattern::parse("crate::models::*").unwrap();
+    assert_eq!(3, module.find_items_matching(&pattern).len());
+    let pattern = crate::utils::path_pattern::PathPattern::parse("**::Model").unwrap();
+    let items = module.find_items_matching(&pattern);
+    assert_eq!(vec!["Model".to_string()], items.iter().map(|item| item.ident()).collect::<Vec<_>>());
+    let pattern = crate::utils::path_pattern::PathPattern::parse("crate::services::*").unwrap();
+    assert!(module.find_items_matching(&pattern).is_empty());
+}
@@ -1,4 +1,4 @@
-use crate::items::item::ItemTrait;
+use crate::items::item::{ItemTrait, VisibilityTrait};
 use crate::walkers::expr::ExprWalker;
 use crate::walkers::fields::FieldsNamedWalker;
 use crate::walkers::generics::GenericsWalker;
@@ -8,7 +8,7 @@ use crate::walkers::statement::StatementWalker;
 use crate::walkers::type_::TypeWalker;
 use crate::walkers::type_param_bound::TypeParamBoundWalker;
 use crate::walkers::Context;
-use syn::TraitItem;
+use syn::{TraitItem, Visibility};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct OtherItem {
@@ -40,6 +40,7 @@ impl ItemTrait for OtherItem {
             syn::Item::TraitAlias(value) => value.ident.to_string(),
             syn::Item::Type(value) => value.ident.to_string(),
             syn::Item::Union(value) => value.ident.to_string(),
+            syn::Item::Mod(value) => value.ident.to_string(),
             _ => panic!("Unsupported type"),
         }
     }
@@ -124,7 +125,48 @@ impl ItemTrait for OtherItem {
                 GenericsWalker::walk(&mut value.generics, context);
                 FieldsNamedWalker::walk(&mut value.fields, context);
             }
+            syn::Item::Mod(value) => {
+                context.predict_ident(&mut value.ident);
+            }
             _ => panic!("Unsupported type"),
         }
     }
-}
\ No newline at end of file
+}
+
+impl VisibilityTrait for OtherItem {
+    fn visibility(&self) -> Option<&Visibility> {
+        match &self.item {
+            syn::Item::Const(value) => Some(&value.vis),
+            syn::Item::Enum(value) => Some(&value.vis),
+            syn::Item::ExternCrate(value) => Some(&value.vis),
+            syn::Item::Fn(value) => Some(&value.vis),
+            syn::Item::Mod(value) => Some(&value.vis),
+            syn::Item::Static(value) => Some(&value.vis),
+            syn::Item::Struct(value) => Some(&value.vis),
+            syn::Item::Trait(value) => Some(&value.vis),
+            syn::Item::TraitAlias(value) => Some(&value.vis),
+            syn::Item::Type(value) => Some(&value.vis),
+            syn::Item::Union(value) => Some(&value.vis),
+            syn::Item::Use(value) => Some(&value.vis),
+            _ => None,
+        }
+    }
+
+    fn set_visibility(&mut self, visibility: Visibility) {
+        match &mut self.item {
+            syn::Item::Const(value) => value.vis = visibility,
+            syn::Item::Enum(value) => value.vis = visibility,
+            syn::Item::ExternCrate(value) => value.vis = visibility,
+            syn::Item::Fn(value) => value.vis = visibility,
+            syn::Item::Mod(value) => value.vis = visibility,
+            syn::Item::Static(value) => value.vis = visibility,
+            syn::Item::Struct(value) => value.vis = visibility,
+            syn::Item::Trait(value) => value.vis = visibility,
+            syn::Item::TraitAlias(value) => value.vis = visibility,
+            syn::Item::Type(value) => value.vis = visibility,
+            syn::Item::Union(value) => value.vis = visibility,
+            syn::Item::Use(value) => value.vis = visibility,
+            _ => {}
+        }
+    }
+}
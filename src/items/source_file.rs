@@ -1,10 +1,160 @@
-use crate::items::item::{Item, ItemTrait};
+use crate::items::item::Item;
 use crate::items::module_item::ModuleItem;
+use crate::items::use_item::{ImportsGranularity, UseItem};
+use crate::error::Error;
+use crate::items::symbol_table::{Namespace, SymbolTable};
+use crate::utils::{create_ident, create_use_leaves_tree};
+use crate::utils::name_conventions::NamingConventions;
 use crate::utils::path::Path;
 use crate::{debug, functions};
 use itertools::Itertools;
-use std::collections::HashMap;
-use syn::Attribute;
+use std::collections::{HashMap, HashSet};
+use crate::walkers::scope::{Scope, ScopeVisitor, ScopeWalker};
+use syn::{Attribute, ItemMod, PathSegment, Visibility};
+
+pub type IdentNamer = Box<dyn Fn(&ModuleItem, &str) -> String>;
+
+/// How [`SourceFile::merge_with_strategy`] resolves items defined with the same ident in
+/// several modules.
+#[derive(Default)]
+pub enum ConflictStrategy {
+    /// Prefix the ident with the module name (e.g. `ModuleFoo`, `module_foo`, `MODULE_FOO`).
+    #[default]
+    Prefix,
+    /// Suffix the ident with the module name (e.g. `FooModule`, `foo_module`, `FOO_MODULE`).
+    Suffix,
+    /// Move the items into a submodule named after the module file (e.g. `module::Foo`).
+    Nest,
+    /// Refuse to merge and report the conflicting idents.
+    Fail,
+    /// Compute the new ident from the module and the conflicting ident.
+    Custom(IdentNamer),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct IdentRename {
+    pub module: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// Rewrites the intra-crate paths of a module relatively to the module resulting of a merge.
+struct MergedPathRelocator<'a> {
+    symbol_table: &'a SymbolTable,
+    module: String,
+    is_root: bool,
+    nested_idents: &'a HashMap<String, Vec<String>>,
+}
+
+impl MergedPathRelocator<'_> {
+    /// Resolve `segments` to the merged module they point into and the segments left after it,
+    /// when they are relative to the crate (`crate::`, `self::`, `super::` or an import).
+    fn resolve(&self, segments: &[PathSegment]) -> Option<(String, Vec<PathSegment>)> {
+        let first_segment = segments.first()?.ident.to_string();
+        let is_relative = match first_segment.as_str() {
+            "crate" | "self" | "super" => true,
+            ident => self.symbol_table.module(&self.module)
+                .and_then(|symbols| symbols.lookup(ident))
+                .map(|symbol| symbol.is_import())
+                .unwrap_or(false),
+        };
+        if !is_relative {
+            return None;
+        }
+        let mut segments = segments.to_vec();
+        if self.is_root && first_segment == "self" {
+            segments[0] = PathSegment::from(create_ident("crate"));
+        }
+        let path = self.symbol_table.resolve(&self.module, &Path::from(segments))?;
+        let segments = path.iter().cloned().collect::<Vec<_>>();
+        if segments[0].ident != "crate" {
+            return None;
+        }
+        let target_module = segments.get(1)
+            .map(|segment| segment.ident.to_string())
+            .filter(|ident| self.symbol_table.module(ident).is_some());
+        match target_module {
+            Some(value) => Some((value, segments[2..].to_vec())),
+            None => Some((String::new(), segments[1..].to_vec())),
+        }
+    }
+
+    /// Prefix `segments` with the generated submodule when they point to a nested item.
+    fn nest(&self, target_module: &str, mut segments: Vec<PathSegment>) -> Vec<PathSegment> {
+        let is_nested = self.nested_idents.get(target_module)
+            .map(|idents| idents.contains(&segments[0].ident.to_string()))
+            .unwrap_or(false);
+        if is_nested {
+            segments.insert(0, PathSegment::from(create_ident(target_module)));
+        }
+        segments
+    }
+
+    /// Rewrite a use item into the imports still needed once merged: imports of merged items
+    /// are dropped, unless they rename the item or reach into it (e.g. an enum variant).
+    fn relocate_use_item(&self, use_item: &UseItem) -> Option<UseItem> {
+        if use_item.0.leading_colon.is_some() {
+            return Some(use_item.clone());
+        }
+        let mut is_relocated = false;
+        let mut leaves = Vec::new();
+        for (path, rename, is_glob) in use_item.leaves() {
+            let segments = path.iter().cloned().collect::<Vec<_>>();
+            let (target_module, rest) = match self.resolve(&segments) {
+                Some(value) => value,
+                None => {
+                    leaves.push((path, rename, is_glob));
+                    continue;
+                }
+            };
+            is_relocated = true;
+            if is_glob || rest.is_empty() {
+                continue;
+            }
+            let is_same_ident = rename.as_ref()
+                .map(|rename| rest[0].ident == rename)
+                .unwrap_or(true);
+            if rest.len() == 1 && is_same_ident {
+                continue;
+            }
+            let mut segments = vec![PathSegment::from(create_ident("self"))];
+            segments.extend(self.nest(&target_module, rest));
+            leaves.push((Path::from(segments), rename, is_glob));
+        }
+        if !is_relocated {
+            return Some(use_item.clone());
+        }
+        if leaves.is_empty() {
+            return None;
+        }
+        let mut use_item = use_item.clone();
+        use_item.0.tree = create_use_leaves_tree(&leaves);
+        Some(use_item)
+    }
+}
+
+impl ScopeVisitor for MergedPathRelocator<'_> {
+    fn visit_path(&mut self, path: &mut syn::Path, namespace: Namespace, scope: &Scope) {
+        if path.leading_colon.is_some() {
+            return;
+        }
+        let segments = path.segments.iter().cloned().collect::<Vec<_>>();
+        let (target_module, rest) = match self.resolve(&segments) {
+            Some((_, rest)) if rest.is_empty() => return,
+            Some(value) => value,
+            None => return,
+        };
+        let mut segments = self.nest(&target_module, rest);
+        let namespace = match segments.len() {
+            1 => namespace,
+            _ => Namespace::Type,
+        };
+        if scope.is_local(&segments[0].ident.to_string(), namespace) {
+            segments.insert(0, PathSegment::from(create_ident("self")));
+        }
+        path.segments = segments.into_iter().collect();
+    }
+}
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct SourceFile {
@@ -20,48 +170,177 @@ impl SourceFile {
         }
     }
 
-    fn resolve_ident_conflicts(&mut self) {
-        let mut ident_duplications = HashMap::new();
-        for module in &self.modules {
-            for item in module.items.iter() {
-                let item_ident = item.ident();
-                if ident_duplications.contains_key(&item_ident) == false {
-                    ident_duplications.insert(item_ident.clone(), Vec::new());
+    pub fn symbol_table(&self) -> SymbolTable {
+        SymbolTable::new(self)
+    }
+
+    /// New ident of `ident` defined in `module`. Prefix and Suffix don't repeat a module name
+    /// the ident already starts or ends with, unless `is_stripping` is false.
+    fn namespaced_ident(strategy: &ConflictStrategy, module: &ModuleItem, ident: &str) -> String {
+        let is_screaming_snake_case = ident.chars()
+            .all(|char| char.is_uppercase() || char.is_ascii_digit() || char == '_');
+        let is_camel_case = ident.starts_with(char::is_uppercase);
+        // the conversions drop single letter names such as `a`, which are then used as is
+        let file_name = &module.file_name;
+        let (module_name, separator) = match (is_screaming_snake_case, is_camel_case) {
+            (true, _) => match NamingConventions::to_screaming_snake_case(file_name) {
+                value if value.is_empty() => (file_name.to_uppercase(), "_"),
+                value => (value, "_"),
+            },
+            (false, true) => match NamingConventions::to_camel_case(file_name) {
+                value if value.is_empty() => (file_name[..1].to_uppercase() + &file_name[1..], ""),
+                value => (value, ""),
+            },
+            (false, false) => match NamingConventions::to_snake_case(file_name) {
+                value if value.is_empty() => (file_name.clone(), "_"),
+                value => (value, "_"),
+            },
+        };
+        match strategy {
+            ConflictStrategy::Prefix => format!("{}{}{}", module_name, separator, ident),
+            ConflictStrategy::Suffix => format!("{}{}{}", ident, separator, module_name),
+            ConflictStrategy::Custom(value) => value(module, ident),
+            ConflictStrategy::Nest | ConflictStrategy::Fail => ident.to_string(),
+        }
+    }
+
+    /// New idents of the conflicting `ident` in each of `modules`, when they are distinct and
+    /// don't collide with an item already defined in `namespace`.
+    fn namespaced_idents(
+        &self,
+        strategy: &ConflictStrategy,
+        modules: &[String],
+        ident: &str,
+        namespace: Namespace,
+        defined_idents: &HashSet<(String, Namespace)>,
+    ) -> Option<Vec<String>> {
+        let new_idents = modules.iter()
+            .map(|module_name| {
+                let module = self.modules.iter()
+                    .find(|module| module.file_name == *module_name)
+                    .unwrap();
+                Self::namespaced_ident(strategy, module, ident)
+            })
+            .collect::<Vec<_>>();
+        let is_unique = new_idents.iter().all_unique()
+            && new_idents.iter()
+                .filter(|new_ident| *new_ident != ident)
+                .all(|new_ident| !defined_idents.contains(&(new_ident.clone(), namespace)));
+        match is_unique {
+            true => Some(new_idents),
+            false => None,
+        }
+    }
+
+    fn resolve_ident_conflicts(&mut self, strategy: &ConflictStrategy) -> Result<Vec<IdentRename>, Error> {
+        let symbol_table = self.symbol_table();
+        let mut defined_idents = HashSet::new();
+        let mut ident_duplications = HashMap::<(String, Namespace), Vec<_>>::new();
+        for module in symbol_table.modules.iter() {
+            for symbol in module.symbols.iter().filter(|symbol| symbol.is_item()) {
+                for namespace in symbol.namespaces.iter() {
+                    defined_idents.insert((symbol.ident.clone(), *namespace));
+                    ident_duplications.entry((symbol.ident.clone(), *namespace))
+                        .or_default()
+                        .push((module.module.clone(), symbol.namespaces.clone()));
                 }
-                let module_names = ident_duplications.get_mut(&item_ident).unwrap();
-                module_names.push(module.name().clone());
             }
         }
-        let ident_duplications = ident_duplications.iter()
-            .filter(|(_, module_names)| module_names.len() > 1)
-            .collect::<HashMap<_, _>>();
-        for (item_ident, module_names) in ident_duplications {
-            for module_name in module_names {
-                let module = self.find_module_by_name(module_name).unwrap();
-                let item = module.find_item_by_ident(item_ident).unwrap();
-                let namespaced_item_ident = item.ident().replace(module_name, "");
-                let namespaced_item_ident = format!("{}{}", module_name, namespaced_item_ident);
-                debug!("Renaming item ident from '{}' to '{}'", item_ident, namespaced_item_ident);
-                functions::rename::from_item(
-                    item,
-                    item_ident,
-                    &namespaced_item_ident,
-                );
-                module.items.iter_mut()
-                    .for_each(|item| {
-                        functions::rename::from_item(
-                            item,
-                            item_ident,
-                            &namespaced_item_ident,
-                        );
-                    });
+        let ident_duplications = ident_duplications.into_iter()
+            .filter(|(_, modules)| modules.len() > 1)
+            .sorted_by(|(a, _), (b, _)| a.cmp(b))
+            .collect::<Vec<_>>();
+        let mut renames = Vec::new();
+        if ident_duplications.is_empty() {
+            return Ok(renames);
+        }
+        match strategy {
+            ConflictStrategy::Fail => {
+                let conflicts = ident_duplications.iter()
+                    .map(|((item_ident, _), modules)| {
+                        let module_names = modules.iter()
+                            .map(|(module_name, _)| format!("'{}'", module_name))
+                            .join(", ");
+                        format!("'{}' in modules {}", item_ident, module_names)
+                    })
+                    .unique()
+                    .join("; ");
+                Err(Error {
+                    description: format!("Conflicting item idents: {}", conflicts),
+                })
+            }
+            ConflictStrategy::Nest => {
+                let mut module_idents: Vec<(String, Vec<String>)> = Vec::new();
+                for ((item_ident, _), modules) in ident_duplications {
+                    for (module_name, _) in modules {
+                        match module_idents.iter_mut().find(|(name, _)| *name == module_name) {
+                            Some((_, idents)) if idents.contains(&item_ident) => {}
+                            Some((_, idents)) => idents.push(item_ident.clone()),
+                            None => module_idents.push((module_name, vec![item_ident.clone()])),
+                        }
+                    }
+                }
+                for (module_name, idents) in module_idents {
+                    let module = self.find_module_by_file_name(&module_name).unwrap();
+                    module.nest_items(&idents);
+                    for item_ident in idents {
+                        renames.push(IdentRename {
+                            to: format!("{}::{}", module_name, item_ident),
+                            module: module_name.clone(),
+                            from: item_ident,
+                        });
+                    }
+                }
+                Ok(renames)
+            }
+            _ => {
+                // Every rename is checked before the first one is applied.
+                let mut planned_renames: Vec<(Namespace, IdentRename)> = Vec::new();
+                for ((item_ident, namespace), modules) in ident_duplications {
+                    let modules = modules.into_iter()
+                        .filter(|(module_name, _)| {
+                            !planned_renames.iter()
+                                .any(|(_, rename)| rename.module == *module_name && rename.from == item_ident)
+                        })
+                        .collect::<Vec<_>>();
+                    let module_names = modules.iter()
+                        .map(|(module_name, _)| module_name.clone())
+                        .collect::<Vec<_>>();
+                    let new_idents = self.namespaced_idents(strategy, &module_names, &item_ident, namespace, &defined_idents)
+                        .ok_or_else(|| Error {
+                            description: format!(
+                                "Cannot resolve conflicting item ident '{}' in modules {} without duplicates",
+                                item_ident,
+                                module_names.iter().map(|module_name| format!("'{}'", module_name)).join(", "),
+                            ),
+                        })?;
+                    for ((module_name, namespaces), new_ident) in modules.into_iter().zip(new_idents) {
+                        if new_ident == item_ident {
+                            continue;
+                        }
+                        for namespace in namespaces {
+                            defined_idents.insert((new_ident.clone(), namespace));
+                        }
+                        planned_renames.push((namespace, IdentRename {
+                            module: module_name,
+                            from: item_ident.clone(),
+                            to: new_ident,
+                        }));
+                    }
+                }
+                for (namespace, rename) in planned_renames {
+                    debug!("Renaming item ident from '{}' to '{}'", rename.from, rename.to);
+                    functions::rename::rename_item(self, &rename.module, namespace, &rename.from, &rename.to);
+                    renames.push(rename);
+                }
+                Ok(renames)
             }
         }
     }
 
-    fn find_module_by_name(&mut self, name: &str) -> Option<&mut ModuleItem> {
+    fn find_module_by_file_name(&mut self, file_name: &str) -> Option<&mut ModuleItem> {
         self.modules.iter_mut()
-            .find(|module| module.name == name)
+            .find(|module| module.file_name == file_name)
     }
 
     pub fn remove_use_items_starting_with(&mut self, prefix: &Path) {
@@ -71,7 +350,69 @@ impl SourceFile {
     }
 
     pub fn merge(&mut self) {
-        self.resolve_ident_conflicts();
+        self.merge_with_strategy(&ConflictStrategy::Prefix)
+            .expect("Prefix strategy failed to resolve the conflicting idents");
+    }
+
+    fn is_root_module(module: &ModuleItem) -> bool {
+        matches!(module.file_name.as_str(), "lib" | "main" | "mod")
+    }
+
+    fn nested_idents(module: &ModuleItem) -> Vec<String> {
+        let items = module.items.iter()
+            .filter_map(|item| match item {
+                Item::Other(value) => match &value.item {
+                    syn::Item::Mod(value) if value.ident == module.file_name => value.content.as_ref(),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .flat_map(|(_, items)| items.iter());
+        items
+            .filter_map(|item| match item {
+                syn::Item::Const(value) => Some(&value.ident),
+                syn::Item::Enum(value) => Some(&value.ident),
+                syn::Item::Fn(value) => Some(&value.sig.ident),
+                syn::Item::Macro(value) => value.ident.as_ref(),
+                syn::Item::Mod(value) => Some(&value.ident),
+                syn::Item::Static(value) => Some(&value.ident),
+                syn::Item::Struct(value) => Some(&value.ident),
+                syn::Item::Trait(value) => Some(&value.ident),
+                syn::Item::TraitAlias(value) => Some(&value.ident),
+                syn::Item::Type(value) => Some(&value.ident),
+                syn::Item::Union(value) => Some(&value.ident),
+                _ => None,
+            })
+            .map(|ident| ident.to_string())
+            .collect()
+    }
+
+    /// Rewrite the `crate::`, `self::` and `super::` paths of every module, including the ones
+    /// going through imports, relatively to the module resulting of the merge.
+    fn relocate_paths(&mut self) {
+        let symbol_table = self.symbol_table();
+        let nested_idents = self.modules.iter()
+            .map(|module| (module.file_name.clone(), Self::nested_idents(module)))
+            .collect::<HashMap<_, _>>();
+        for module in self.modules.iter_mut() {
+            let mut relocator = MergedPathRelocator {
+                symbol_table: &symbol_table,
+                module: module.file_name.clone(),
+                is_root: Self::is_root_module(module),
+                nested_idents: &nested_idents,
+            };
+            ScopeWalker::new(&mut relocator).walk_module_in(module, &symbol_table);
+            module.use_items = module.use_items.iter()
+                .filter_map(|use_item| relocator.relocate_use_item(use_item))
+                .collect();
+        }
+    }
+
+    /// Merge every module into a single one where each module becomes an inline `pub mod`
+    /// named after its file, so that no ident conflict has to be resolved. `crate::` paths
+    /// are rewritten relatively to the merged module so the result compiles wherever it is
+    /// included. Items of a root module (`lib`, `main` or `mod`) stay at the top level.
+    pub fn merge_nested(&mut self) {
         let mut module_result = ModuleItem {
             name: "".to_string(),
             file_name: "".to_string(),
@@ -79,7 +420,41 @@ impl SourceFile {
             use_items: Vec::new(),
             items: Vec::new(),
         };
-        let mut use_reexport_path_prefixes = self.modules.iter()
+        for module in self.modules.iter_mut() {
+            if Self::is_root_module(module) {
+                module.rename_path_root("crate", "self");
+                module_result.extern_crate_items.append(&mut module.extern_crate_items);
+                module_result.use_items.append(&mut module.use_items);
+                module_result.items.append(&mut module.items);
+                continue;
+            }
+            module.rename_path_root("crate", "super");
+            module_result.push_item(syn::Item::Mod(ItemMod {
+                attrs: vec![],
+                vis: Visibility::Public(Default::default()),
+                unsafety: None,
+                mod_token: Default::default(),
+                ident: create_ident(&module.file_name),
+                content: Some((Default::default(), module.decompose())),
+                semi: None,
+            }));
+        }
+        self.modules = vec![module_result];
+    }
+
+    /// Merge every module into a single one, resolving duplicated item idents with `strategy`.
+    /// Returns the renames performed to resolve the conflicts.
+    pub fn merge_with_strategy(&mut self, strategy: &ConflictStrategy) -> Result<Vec<IdentRename>, Error> {
+        let renames = self.resolve_ident_conflicts(strategy)?;
+        self.relocate_paths();
+        let mut module_result = ModuleItem {
+            name: "".to_string(),
+            file_name: "".to_string(),
+            extern_crate_items: Vec::new(),
+            use_items: Vec::new(),
+            items: Vec::new(),
+        };
+        let use_reexport_path_prefixes = self.modules.iter()
             .map(|module| {
                 let mut path = Path::new("self");
                 path.join(module.file_name.clone());
@@ -95,17 +470,9 @@ impl SourceFile {
             .unique_by(|item| item.ident.to_string())
             .map(|item| item.clone())
             .collect::<Vec<_>>();
-        module_result.use_items = module_result.use_items.iter_mut()
-            .unique_by(|item| item.to_string())
-            .map(|item| item.clone())
-            .collect::<Vec<_>>();
-        for path_prefix in use_reexport_path_prefixes.iter_mut() {
-            module_result.use_items.retain_mut(|item| {
-                match item.start_with(path_prefix) {
-                    true => false,
-                    false => true,
-                }
-            });
+        module_result.use_items = UseItem::normalize(&module_result.use_items, ImportsGranularity::Crate);
+        for path_prefix in use_reexport_path_prefixes.iter() {
+            module_result.remove_use_items_starting_with(path_prefix);
         }
         module_result.items = module_result.items.iter()
             .sorted_by(|a, b| {
@@ -125,6 +492,7 @@ impl SourceFile {
             })
             .map(|item| item.clone())
             .collect();
-        self.modules = vec![module_result]
+        self.modules = vec![module_result];
+        Ok(renames)
     }
 }
\ No newline at end of file
@@ -0,0 +1,450 @@
+use crate::items::source_file::{ConflictStrategy, IdentRename, SourceFile};
+use crate::test_utils::fixtures::source_file;
+use pretty_assertions::assert_eq;
+use quote::quote;
+use rstest::rstest;
+
+fn conflicting_source_file() -> SourceFile {
+    source_file(vec![
+        ("alpha", quote! {
+            pub struct Config;
+
+            pub const LIMIT: u32 = 1;
+
+            impl Config {
+                pub fn limit() -> u32 {
+                    LIMIT
+                }
+            }
+        }),
+        ("beta", quote! {
+            pub struct Config {
+                value: u32
+            }
+
+            pub const LIMIT: u32 = 2;
+        }),
+    ])
+}
+
+fn merged_tokens(source_file: &SourceFile) -> String {
+    let items = source_file.modules[0].decompose();
+    quote!(#(#items)*).to_string()
+}
+
+fn rename(module: &str, from: &str, to: &str) -> IdentRename {
+    IdentRename {
+        module: module.to_string(),
+        from: from.to_string(),
+        to: to.to_string(),
+    }
+}
+
+#[rstest]
+fn merge_with_prefix() {
+    let mut source_file = conflicting_source_file();
+    let renames = source_file.merge_with_strategy(&ConflictStrategy::Prefix).unwrap();
+    let expected_quote = quote! {
+        pub struct AlphaConfig;
+
+        impl AlphaConfig {
+            pub fn limit() -> u32 {
+                ALPHA_LIMIT
+            }
+        }
+
+        pub const ALPHA_LIMIT: u32 = 1;
+
+        pub struct BetaConfig {
+            value: u32
+        }
+
+        pub const BETA_LIMIT: u32 = 2;
+    };
+    assert_eq!(expected_quote.to_string(), merged_tokens(&source_file));
+    assert_eq!(
+        vec![
+            rename("alpha", "Config", "AlphaConfig"),
+            rename("beta", "Config", "BetaConfig"),
+            rename("alpha", "LIMIT", "ALPHA_LIMIT"),
+            rename("beta", "LIMIT", "BETA_LIMIT"),
+        ],
+        renames
+    );
+}
+
+#[rstest]
+fn merge_with_suffix() {
+    let mut source_file = conflicting_source_file();
+    let renames = source_file.merge_with_strategy(&ConflictStrategy::Suffix).unwrap();
+    let expected_quote = quote! {
+        pub struct ConfigAlpha;
+
+        impl ConfigAlpha {
+            pub fn limit() -> u32 {
+                LIMIT_ALPHA
+            }
+        }
+
+        pub const LIMIT_ALPHA: u32 = 1;
+
+        pub struct ConfigBeta {
+            value: u32
+        }
+
+        pub const LIMIT_BETA: u32 = 2;
+    };
+    assert_eq!(expected_quote.to_string(), merged_tokens(&source_file));
+    assert_eq!(4, renames.len());
+}
+
+#[rstest]
+fn merge_with_custom() {
+    let mut source_file = conflicting_source_file();
+    let strategy = ConflictStrategy::Custom(Box::new(|module, ident| {
+        format!("{}{}", ident, module.file_name.len())
+    }));
+    let renames = source_file.merge_with_strategy(&strategy).unwrap();
+    assert_eq!(
+        vec![
+            rename("alpha", "Config", "Config5"),
+            rename("beta", "Config", "Config4"),
+            rename("alpha", "LIMIT", "LIMIT5"),
+            rename("beta", "LIMIT", "LIMIT4"),
+        ],
+        renames
+    );
+}
+
+#[rstest]
+fn merge_with_nest() {
+    let mut source_file = source_file(vec![
+        ("alpha", quote! {
+            pub struct Config;
+
+            pub fn build() -> Config {
+                let config: Config = Config;
+                config
+            }
+        }),
+        ("beta", quote! {
+            struct Config {
+                value: u32
+            }
+
+            impl Config {
+                fn new() -> Self {
+                    Self { value: 0 }
+                }
+            }
+        }),
+    ]);
+    let renames = source_file.merge_with_strategy(&ConflictStrategy::Nest).unwrap();
+    let expected_quote = quote! {
+        pub fn build() -> alpha::Config {
+            let config: alpha::Config = alpha::Config;
+            config
+        }
+
+        pub mod alpha {
+            use super::*;
+
+            pub struct Config;
+        }
+
+        pub mod beta {
+            use super::*;
+
+            pub(super) struct Config {
+                pub(super) value: u32
+            }
+
+            impl Config {
+                pub(super) fn new() -> Self {
+                    Self { value: 0 }
+                }
+            }
+        }
+    };
+    assert_eq!(expected_quote.to_string(), merged_tokens(&source_file));
+    assert_eq!(
+        vec![
+            rename("alpha", "Config", "alpha::Config"),
+            rename("beta", "Config", "beta::Config"),
+        ],
+        renames
+    );
+}
+
+#[rstest]
+fn merge_with_nest_and_cross_module_references() {
+    let mut source_file = source_file(vec![
+        ("alpha", quote! {
+            pub struct Foo;
+
+            pub enum Bar {
+                A,
+            }
+        }),
+        ("beta", quote! {
+            use crate::alpha::Bar as Baz;
+            pub use crate::alpha::Foo as Reexport;
+
+            pub struct Foo {
+                f: crate::alpha::Foo,
+            }
+
+            pub enum Bar {
+                B(Baz),
+            }
+
+            fn k() -> crate::alpha::Bar {
+                crate::alpha::Bar::A
+            }
+
+            fn z() -> Reexport {
+                crate::alpha::Foo
+            }
+        }),
+    ]);
+    source_file.merge_with_strategy(&ConflictStrategy::Nest).unwrap();
+    let expected_quote = quote! {
+        pub mod alpha {
+            use super::*;
+
+            pub struct Foo;
+
+            pub enum Bar {
+                A,
+            }
+        }
+
+        fn k() -> alpha::Bar {
+            alpha::Bar::A
+        }
+
+        fn z() -> alpha::Foo {
+            alpha::Foo
+        }
+
+        pub mod beta {
+            use super::*;
+
+            pub struct Foo {
+                pub(super) f: alpha::Foo,
+            }
+
+            pub enum Bar {
+                B(alpha::Bar),
+            }
+        }
+    };
+    assert_eq!(expected_quote.to_string(), merged_tokens(&source_file));
+}
+
+#[rstest]
+fn merge_with_fail() {
+    let mut source_file = conflicting_source_file();
+    let error = source_file.merge_with_strategy(&ConflictStrategy::Fail).unwrap_err();
+    assert_eq!(
+        "Conflicting item idents: 'Config' in modules 'alpha', 'beta'; 'LIMIT' in modules 'alpha', 'beta'",
+        error.description
+    );
+    assert_eq!(2, source_file.modules.len());
+}
+
+#[rstest]
+fn merge_nested() {
+    let mut source_file = source_file(vec![
+        ("mod", quote! {
+            pub use self::alpha::Config;
+
+            pub fn default_config() -> crate::alpha::Config {
+                Config::new()
+            }
+        }),
+        ("alpha", quote! {
+            use crate::beta::Limit;
+
+            pub struct Config {
+                limit: Limit
+            }
+
+            impl Config {
+                pub fn new() -> Self {
+                    Self { limit: crate::beta::Limit(super::beta::DEFAULT) }
+                }
+            }
+        }),
+        ("beta", quote! {
+            pub struct Limit(pub u32);
+
+            pub const DEFAULT: u32 = 1;
+        }),
+    ]);
+    source_file.merge_nested();
+    let expected_quote = quote! {
+        pub use self::alpha::Config;
+
+        pub fn default_config() -> self::alpha::Config {
+            Config::new()
+        }
+
+        pub mod alpha {
+            use super::beta::Limit;
+
+            pub struct Config {
+                limit: Limit
+            }
+
+            impl Config {
+                pub fn new() -> Self {
+                    Self { limit: super::beta::Limit(super::beta::DEFAULT) }
+                }
+            }
+        }
+
+        pub mod beta {
+            pub struct Limit(pub u32);
+
+            pub const DEFAULT: u32 = 1;
+        }
+    };
+    assert_eq!(1, source_file.modules.len());
+    assert_eq!(expected_quote.to_string(), merged_tokens(&source_file));
+}
+
+#[rstest]
+fn merge_relocates_paths() {
+    let mut source_file = source_file(vec![
+        ("mod", quote! {
+            pub use self::alpha::*;
+            pub use self::beta::Shape;
+        }),
+        ("alpha", quote! {
+            use super::beta;
+            use crate::beta::{Shape, Shape::Circle, Limit as Max};
+            use std::collections::HashMap;
+
+            pub fn area(shape: crate::beta::Shape, limit: Max) -> u32 {
+                let beta = 1;
+                match shape {
+                    Circle(radius) => radius * beta::factor(),
+                    super::beta::Shape::Square(side) => side * crate::beta::factor(),
+                }
+            }
+        }),
+        ("beta", quote! {
+            pub enum Shape {
+                Circle(u32),
+                Square(u32),
+            }
+
+            pub struct Limit;
+
+            pub fn factor() -> u32 {
+                let factor = 2;
+                factor * super::alpha::area(self::Shape::Circle(1), Limit)
+            }
+        }),
+    ]);
+    source_file.merge();
+    let expected_quote = quote! {
+        use std::collections::HashMap;
+        use self::{Limit as Max, Shape::Circle};
+
+        pub fn area(shape: Shape, limit: Limit) -> u32 {
+            let beta = 1;
+            match shape {
+                Shape::Circle(radius) => radius * factor(),
+                Shape::Square(side) => side * factor(),
+            }
+        }
+
+        pub enum Shape {
+            Circle(u32),
+            Square(u32),
+        }
+
+        pub struct Limit;
+
+        pub fn factor() -> u32 {
+            let factor = 2;
+            factor * area(Shape::Circle(1), Limit)
+        }
+    };
+    assert_eq!(expected_quote.to_string(), merged_tokens(&source_file));
+}
+
+#[rstest]
+#[case::prefix(ConflictStrategy::Prefix, vec![rename("foo", "FooBarX", "FooFooBarX"), rename("foo_bar", "FooBarX", "FooBarFooBarX")])]
+#[case::suffix(ConflictStrategy::Suffix, vec![rename("bar", "XFooBar", "XFooBarBar"), rename("foo_bar", "XFooBar", "XFooBarFooBar")])]
+fn merge_without_duplicated_renames(#[case] strategy: ConflictStrategy, #[case] expected_renames: Vec<IdentRename>) {
+    let (first_module, second_module, ident) = match strategy {
+        ConflictStrategy::Prefix => ("foo", "foo_bar", quote!(FooBarX)),
+        _ => ("bar", "foo_bar", quote!(XFooBar)),
+    };
+    let mut source_file = source_file(vec![
+        (first_module, quote! {
+            pub struct #ident;
+        }),
+        (second_module, quote! {
+            pub struct #ident;
+        }),
+    ]);
+    let renames = source_file.merge_with_strategy(&strategy).unwrap();
+    assert_eq!(expected_renames, renames);
+}
+
+#[rstest]
+#[case::prefix(ConflictStrategy::Prefix, vec![rename("a", "Bar", "ABar"), rename("b", "Bar", "BBar"), rename("a", "run", "a_run"), rename("b", "run", "b_run")])]
+#[case::suffix(ConflictStrategy::Suffix, vec![rename("a", "Bar", "BarA"), rename("b", "Bar", "BarB"), rename("a", "run", "run_a"), rename("b", "run", "run_b")])]
+fn merge_with_single_letter_modules(#[case] strategy: ConflictStrategy, #[case] expected_renames: Vec<IdentRename>) {
+    let mut source_file = source_file(vec![
+        ("a", quote! {
+            pub struct Bar;
+
+            pub fn run() {}
+        }),
+        ("b", quote! {
+            pub struct Bar;
+
+            pub fn run() {}
+        }),
+    ]);
+    let renames = source_file.merge_with_strategy(&strategy).unwrap();
+    assert_eq!(expected_renames, renames);
+}
+
+#[rstest]
+fn merge_with_unresolvable_duplicates() {
+    let mut source_file = conflicting_source_file();
+    let strategy = ConflictStrategy::Custom(Box::new(|_, ident| format!("{}2", ident)));
+    let error = source_file.merge_with_strategy(&strategy).unwrap_err();
+    assert_eq!(
+        "Cannot resolve conflicting item ident 'Config' in modules 'alpha', 'beta' without duplicates",
+        error.description
+    );
+    assert_eq!(2, source_file.modules.len());
+}
+
+#[rstest]
+fn merge_with_same_ident_in_different_namespaces() {
+    let mut source_file = source_file(vec![
+        ("alpha", quote! {
+            pub struct Foo {
+                value: u32
+            }
+
+            #[allow(non_snake_case)]
+            pub fn Foo() -> Foo {
+                Foo { value: 0 }
+            }
+        }),
+        ("beta", quote! {
+            pub struct Bar;
+        }),
+    ]);
+    let renames = source_file.merge_with_strategy(&ConflictStrategy::Fail).unwrap();
+    assert_eq!(Vec::<IdentRename>::new(), renames);
+}
@@ -1,9 +1,9 @@
 use crate::items::implementation_item::ImplementationItem;
-use crate::items::item::ItemTrait;
+use crate::items::item::{ItemTrait, VisibilityTrait};
 use crate::walkers::fields::FieldsWalker;
 use crate::walkers::generics::GenericsWalker;
 use crate::walkers::Context;
-use syn::{ItemImpl, ItemStruct};
+use syn::{ItemImpl, ItemStruct, Visibility};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct StructItem {
@@ -35,4 +35,14 @@ impl ItemTrait for StructItem {
             impl_item.walk(context);
         }
     }
-}
\ No newline at end of file
+}
+
+impl VisibilityTrait for StructItem {
+    fn visibility(&self) -> Option<&Visibility> {
+        Some(&self.item.vis)
+    }
+
+    fn set_visibility(&mut self, visibility: Visibility) {
+        self.item.vis = visibility;
+    }
+}
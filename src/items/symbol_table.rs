@@ -0,0 +1,382 @@
+use crate::items::item::{Item, ItemTrait};
+use crate::items::module_item::ModuleItem;
+use crate::items::source_file::SourceFile;
+use crate::items::use_item::UseItem;
+use crate::utils::path::Path;
+use std::collections::HashSet;
+use syn::{Fields, PathSegment, Visibility};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Namespace {
+    Type,
+    Value,
+    Macro,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SymbolKind {
+    Struct,
+    Enum,
+    Fn,
+    Const,
+    Static,
+    Trait,
+    TraitAlias,
+    Type,
+    Union,
+    Macro,
+    Module,
+    Import,
+    ExternCrate,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Symbol {
+    /// Name under which the symbol is visible in its module.
+    pub ident: String,
+    pub kind: SymbolKind,
+    /// Absolute path of a defined item (e.g. `crate::module::Foo`), imported path of an import
+    /// or crate name of an extern crate.
+    pub path: Path,
+    pub namespaces: Vec<Namespace>,
+    pub visibility: Visibility,
+}
+
+impl Symbol {
+    pub fn is_import(&self) -> bool {
+        self.kind == SymbolKind::Import
+    }
+
+    pub fn is_extern_crate(&self) -> bool {
+        self.kind == SymbolKind::ExternCrate
+    }
+
+    pub fn is_item(&self) -> bool {
+        !self.is_import() && !self.is_extern_crate()
+    }
+
+    pub fn in_namespace(&self, namespace: Namespace) -> bool {
+        self.namespaces.contains(&namespace)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ModuleSymbols {
+    pub module: String,
+    pub symbols: Vec<Symbol>,
+    pub globs: Vec<Path>,
+}
+
+impl ModuleSymbols {
+    pub fn new(module: &ModuleItem) -> Self {
+        let mut symbols = Self {
+            module: module.file_name.clone(),
+            symbols: Vec::new(),
+            globs: Vec::new(),
+        };
+        for item in module.items.iter() {
+            symbols.push_item(item);
+        }
+        for use_item in module.use_items.iter() {
+            symbols.push_use_item(use_item);
+        }
+        for extern_crate_item in module.extern_crate_items.iter() {
+            let crate_name = extern_crate_item.ident.to_string();
+            let ident = match extern_crate_item.rename {
+                Some((_, ref rename)) => rename.to_string(),
+                None => crate_name.clone(),
+            };
+            if ident == "_" {
+                continue;
+            }
+            symbols.symbols.push(Symbol {
+                ident,
+                kind: SymbolKind::ExternCrate,
+                path: Path::new(crate_name),
+                namespaces: vec![Namespace::Type],
+                visibility: extern_crate_item.vis.clone(),
+            });
+        }
+        symbols
+    }
+
+    fn item_path(&self, ident: &str) -> Path {
+        let mut path = SymbolTable::module_path(&self.module);
+        path.join(ident);
+        path
+    }
+
+    fn push_symbol(&mut self, ident: String, kind: SymbolKind, namespaces: Vec<Namespace>, visibility: &Visibility) {
+        self.symbols.push(Symbol {
+            path: self.item_path(&ident),
+            ident,
+            kind,
+            namespaces,
+            visibility: visibility.clone(),
+        });
+    }
+
+    fn push_item(&mut self, item: &Item) {
+        match item {
+            Item::Struct(value) => {
+                let namespaces = match value.item.fields {
+                    Fields::Named(_) => vec![Namespace::Type],
+                    _ => vec![Namespace::Type, Namespace::Value],
+                };
+                self.push_symbol(item.ident(), SymbolKind::Struct, namespaces, &value.item.vis);
+            }
+            Item::Enum(value) => {
+                self.push_symbol(item.ident(), SymbolKind::Enum, vec![Namespace::Type], &value.item.vis);
+            }
+            Item::Fn(value) => {
+                let visibility = value.visibility().clone();
+                self.push_symbol(item.ident(), SymbolKind::Fn, vec![Namespace::Value], &visibility);
+            }
+            Item::Other(value) => {
+                let (ident, kind, namespaces, visibility) = match &value.item {
+                    syn::Item::Const(value) => {
+                        (value.ident.to_string(), SymbolKind::Const, vec![Namespace::Value], &value.vis)
+                    }
+                    syn::Item::Static(value) => {
+                        (value.ident.to_string(), SymbolKind::Static, vec![Namespace::Value], &value.vis)
+                    }
+                    syn::Item::Trait(value) => {
+                        (value.ident.to_string(), SymbolKind::Trait, vec![Namespace::Type], &value.vis)
+                    }
+                    syn::Item::TraitAlias(value) => {
+                        (value.ident.to_string(), SymbolKind::TraitAlias, vec![Namespace::Type], &value.vis)
+                    }
+                    syn::Item::Type(value) => {
+                        (value.ident.to_string(), SymbolKind::Type, vec![Namespace::Type], &value.vis)
+                    }
+                    syn::Item::Union(value) => {
+                        (value.ident.to_string(), SymbolKind::Union, vec![Namespace::Type], &value.vis)
+                    }
+                    syn::Item::Mod(value) => {
+                        (value.ident.to_string(), SymbolKind::Module, vec![Namespace::Type], &value.vis)
+                    }
+                    syn::Item::Fn(value) => {
+                        (value.sig.ident.to_string(), SymbolKind::Fn, vec![Namespace::Value], &value.vis)
+                    }
+                    syn::Item::Macro(value) => {
+                        match value.ident {
+                            Some(ref ident) => {
+                                (ident.to_string(), SymbolKind::Macro, vec![Namespace::Macro], &Visibility::Inherited)
+                            }
+                            None => return,
+                        }
+                    }
+                    _ => return,
+                };
+                self.push_symbol(ident, kind, namespaces, visibility);
+            }
+        }
+    }
+
+    fn push_use_item(&mut self, use_item: &UseItem) {
+        for (path, rename, is_glob) in use_item.leaves() {
+            if is_glob {
+                self.globs.push(path);
+                continue;
+            }
+            let ident = match rename {
+                Some(value) if value == "_" => continue,
+                Some(value) => value,
+                None => path.last().unwrap().ident.to_string(),
+            };
+            self.push_import(ident, path, &use_item.0.vis);
+        }
+    }
+
+    fn push_import(&mut self, ident: String, path: Path, visibility: &Visibility) {
+        self.symbols.push(Symbol {
+            ident,
+            kind: SymbolKind::Import,
+            path,
+            namespaces: vec![Namespace::Type, Namespace::Value, Namespace::Macro],
+            visibility: visibility.clone(),
+        });
+    }
+
+    pub fn symbols(&self) -> &Vec<Symbol> {
+        &self.symbols
+    }
+
+    pub fn globs(&self) -> &Vec<Path> {
+        &self.globs
+    }
+
+    pub fn lookup(&self, ident: &str) -> Option<&Symbol> {
+        self.symbols.iter()
+            .find(|symbol| symbol.ident == ident)
+    }
+
+    pub fn lookup_in(&self, ident: &str, namespace: Namespace) -> Option<&Symbol> {
+        self.symbols.iter()
+            .filter(|symbol| symbol.in_namespace(namespace))
+            .find(|symbol| symbol.ident == ident)
+    }
+
+    pub fn contains(&self, ident: &str) -> bool {
+        self.lookup(ident).is_some()
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SymbolTable {
+    pub modules: Vec<ModuleSymbols>,
+}
+
+impl SymbolTable {
+    pub fn new(source_file: &SourceFile) -> Self {
+        Self {
+            modules: source_file.modules.iter()
+                .map(ModuleSymbols::new)
+                .collect(),
+        }
+    }
+
+    pub fn module(&self, module: &str) -> Option<&ModuleSymbols> {
+        self.modules.iter()
+            .find(|symbols| symbols.module == module)
+    }
+
+    /// Find the module targeted by a path such as `crate::module`, `super::module`,
+    /// `self::module` or `module`.
+    pub fn module_of(&self, path: &Path) -> Option<&ModuleSymbols> {
+        let segments = path.iter()
+            .map(|segment| segment.ident.to_string())
+            .filter(|segment| !["crate", "self", "super"].contains(&segment.as_str()))
+            .collect::<Vec<_>>();
+        match segments.as_slice() {
+            [] => self.module(""),
+            [module] => self.module(module),
+            _ => None,
+        }
+    }
+
+    pub fn lookup(&self, module: &str, ident: &str) -> Option<&Symbol> {
+        self.internal_lookup(module, ident, None, &mut HashSet::new())
+    }
+
+    pub fn lookup_in(&self, module: &str, ident: &str, namespace: Namespace) -> Option<&Symbol> {
+        self.internal_lookup(module, ident, Some(namespace), &mut HashSet::new())
+    }
+
+    fn internal_lookup(
+        &self,
+        module: &str,
+        ident: &str,
+        namespace: Option<Namespace>,
+        visited_modules: &mut HashSet<String>,
+    ) -> Option<&Symbol> {
+        if !visited_modules.insert(module.to_string()) {
+            return None;
+        }
+        let symbols = self.module(module)?;
+        let symbol = match namespace {
+            Some(namespace) => symbols.lookup_in(ident, namespace),
+            None => symbols.lookup(ident),
+        };
+        if symbol.is_some() {
+            return symbol;
+        }
+        for glob in symbols.globs.iter() {
+            let glob_module = match self.module_of(glob) {
+                Some(value) => value.module.clone(),
+                None => continue,
+            };
+            let symbol = self.internal_lookup(&glob_module, ident, namespace, visited_modules);
+            if symbol.is_some() {
+                return symbol;
+            }
+        }
+        None
+    }
+
+    /// Resolve a path as written in `module` to its absolute form, e.g. `Foo::new` to
+    /// `crate::module::Foo::new` or `HashMap` to `std::collections::HashMap`.
+    pub fn resolve(&self, module: &str, path: &Path) -> Option<Path> {
+        self.internal_resolve(module, path, &mut HashSet::new())
+    }
+
+    fn internal_resolve(&self, module: &str, path: &Path, visited_paths: &mut HashSet<String>) -> Option<Path> {
+        let segments = path.iter().cloned().collect::<Vec<_>>();
+        let first_segment = segments.first()?.ident.to_string();
+        let mut resolved_segments: Vec<PathSegment> = match first_segment.as_str() {
+            "crate" => return Some(path.clone()),
+            "self" => Self::module_path(module).iter().cloned().collect(),
+            "super" => Self::module_path("").iter().cloned().collect(),
+            _ => {
+                let symbol = self.lookup(module, &first_segment)?;
+                let symbol_path = match symbol.kind {
+                    SymbolKind::Import => {
+                        let path_string = format!("{}::{}", module, symbol.path);
+                        if !visited_paths.insert(path_string) {
+                            return None;
+                        }
+                        let symbol_module = self.module_of_symbol(module, symbol);
+                        self.internal_resolve(&symbol_module, &symbol.path, visited_paths)
+                            .unwrap_or(symbol.path.clone())
+                    }
+                    _ => symbol.path.clone(),
+                };
+                symbol_path.iter().cloned().collect()
+            }
+        };
+        resolved_segments.extend(segments.into_iter().skip(1));
+        Some(Path::from(resolved_segments))
+    }
+
+    /// Resolve a path like [`SymbolTable::resolve`] then follow imports and re-exports
+    /// (`pub use`) of crate modules up to the path of the defining item.
+    pub fn resolve_item(&self, module: &str, path: &Path) -> Option<Path> {
+        let mut path = self.resolve(module, path)?;
+        let mut visited_paths = HashSet::new();
+        loop {
+            let segments = path.iter().cloned().collect::<Vec<_>>();
+            if segments.len() < 2 || segments[0].ident != "crate" {
+                return Some(path);
+            }
+            let second_segment = segments[1].ident.to_string();
+            let (module, index) = match self.module(&second_segment) {
+                Some(_) if segments.len() > 2 => (second_segment, 2),
+                _ => (String::new(), 1),
+            };
+            let symbol = match self.module(&module).and_then(|symbols| symbols.lookup(&segments[index].ident.to_string())) {
+                Some(value) if value.is_import() => value,
+                _ => return Some(path),
+            };
+            if !visited_paths.insert(path.to_string()) {
+                return Some(path);
+            }
+            let mut resolved_segments = self.resolve(&module, &symbol.path)?
+                .iter()
+                .cloned()
+                .collect::<Vec<_>>();
+            resolved_segments.extend(segments.into_iter().skip(index + 1));
+            path = Path::from(resolved_segments);
+        }
+    }
+
+    fn module_path(module: &str) -> Path {
+        let mut path = Path::new("crate");
+        if !module.is_empty() {
+            path.join(module);
+        }
+        path
+    }
+
+    fn module_of_symbol(&self, module: &str, symbol: &Symbol) -> String {
+        let is_own_symbol = self.module(module)
+            .map(|symbols| symbols.symbols.contains(symbol))
+            .unwrap_or(false);
+        if is_own_symbol {
+            return module.to_string();
+        }
+        self.modules.iter()
+            .find(|symbols| symbols.symbols.contains(symbol))
+            .map(|symbols| symbols.module.clone())
+            .unwrap_or(module.to_string())
+    }
+}
@@ -0,0 +1,123 @@
+use crate::items::symbol_table::{Namespace, SymbolKind};
+use crate::test_utils::fixtures::source_file;
+use crate::utils::path::Path;
+use pretty_assertions::assert_eq;
+use quote::quote;
+use rstest::rstest;
+
+#[rstest]
+fn with_items() {
+    let source_file = source_file(vec![
+        ("a", quote! {
+            struct Foo {
+                field: u32
+            }
+
+            struct Bar(u32);
+
+            enum Baz {}
+
+            fn qux() {}
+
+            const QUUX: u32 = 0;
+        }),
+    ]);
+    let symbol_table = source_file.symbol_table();
+    let symbols = symbol_table.module("a").unwrap();
+    assert_eq!(
+        vec!["Foo", "Bar", "Baz", "qux", "QUUX"],
+        symbols.symbols().iter().map(|symbol| symbol.ident.as_str()).collect::<Vec<_>>()
+    );
+    let symbol = symbols.lookup("Foo").unwrap();
+    assert_eq!(SymbolKind::Struct, symbol.kind);
+    assert_eq!(Path::new("crate").join("a").join("Foo").to_owned(), symbol.path);
+    assert_eq!(None, symbols.lookup_in("Foo", Namespace::Value));
+    assert_eq!(true, symbols.lookup_in("Bar", Namespace::Value).is_some());
+    assert_eq!(SymbolKind::Const, symbols.lookup_in("QUUX", Namespace::Value).unwrap().kind);
+}
+
+#[rstest]
+fn with_use_items() {
+    let source_file = source_file(vec![
+        ("a", quote! {
+            extern crate serde as serde_crate;
+
+            use std::collections::HashMap;
+            use std::fmt::{self, Display, Debug as Dbg};
+            use std::io::*;
+            use std::path::PathBuf as _;
+        }),
+    ]);
+    let symbol_table = source_file.symbol_table();
+    let symbols = symbol_table.module("a").unwrap();
+    assert_eq!(
+        vec!["HashMap", "fmt", "Display", "Dbg", "serde_crate"],
+        symbols.symbols().iter().map(|symbol| symbol.ident.as_str()).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        Path::new("std").join("collections").join("HashMap").to_owned(),
+        symbols.lookup("HashMap").unwrap().path
+    );
+    assert_eq!(
+        Path::new("std").join("fmt").to_owned(),
+        symbols.lookup("fmt").unwrap().path
+    );
+    assert_eq!(
+        Path::new("std").join("fmt").join("Debug").to_owned(),
+        symbols.lookup("Dbg").unwrap().path
+    );
+    assert_eq!(SymbolKind::ExternCrate, symbols.lookup("serde_crate").unwrap().kind);
+    assert_eq!(Path::new("serde"), symbols.lookup("serde_crate").unwrap().path);
+    assert_eq!(&vec![Path::new("std").join("io").to_owned()], symbols.globs());
+}
+
+#[rstest]
+fn lookup_through_glob() {
+    let source_file = source_file(vec![
+        ("a", quote! {
+            use crate::b::*;
+        }),
+        ("b", quote! {
+            use super::a::*;
+
+            struct Foo;
+        }),
+    ]);
+    let symbol_table = source_file.symbol_table();
+    let symbol = symbol_table.lookup("a", "Foo").unwrap();
+    assert_eq!(Path::new("crate").join("b").join("Foo").to_owned(), symbol.path);
+    assert_eq!(None, symbol_table.lookup("a", "Bar"));
+}
+
+#[rstest]
+fn resolve() {
+    let source_file = source_file(vec![
+        ("a", quote! {
+            use std::collections::HashMap;
+            use super::b::Bar as Baz;
+
+            struct Foo;
+        }),
+        ("b", quote! {
+            struct Bar;
+        }),
+    ]);
+    let symbol_table = source_file.symbol_table();
+    assert_eq!(
+        Some(Path::new("crate").join("a").join("Foo").join("new").to_owned()),
+        symbol_table.resolve("a", &Path::new("Foo").join("new").to_owned())
+    );
+    assert_eq!(
+        Some(Path::new("std").join("collections").join("HashMap").to_owned()),
+        symbol_table.resolve("a", &Path::new("HashMap"))
+    );
+    assert_eq!(
+        Some(Path::new("crate").join("b").join("Bar").to_owned()),
+        symbol_table.resolve("a", &Path::new("Baz"))
+    );
+    assert_eq!(
+        Some(Path::new("crate").join("a").join("Foo").to_owned()),
+        symbol_table.resolve("b", &Path::new("super").join("a").join("Foo").to_owned())
+    );
+    assert_eq!(None, symbol_table.resolve("a", &Path::new("Unknown")));
+}
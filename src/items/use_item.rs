@@ -1,79 +1,325 @@
+use crate::utils::{create_ident, create_use, create_use_leaves_tree};
 use crate::utils::path::Path;
+use crate::walkers::Context;
+use itertools::Itertools;
 use quote::ToTokens;
-use std::collections::VecDeque;
-use syn::UseTree;
+use syn::{UseTree, Visibility};
+
+/// How [`UseItem::normalize`] splits imports into use items, mirroring rustfmt's
+/// `imports_granularity` option.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum ImportsGranularity {
+    /// One use item per top-level crate, e.g. `use std::{fmt::Display, io};`.
+    #[default]
+    Crate,
+    /// One use item per module, e.g. `use std::fmt::{Debug, Display};`.
+    Module,
+    /// One use item per import, e.g. `use std::fmt::Display;`.
+    Item,
+    /// A single use item, e.g. `use {crate::Foo, std::fmt::Display};`.
+    One,
+}
+
+/// Imports are ordered by bucket: `std`, `core` and `alloc` first, then external crates and
+/// finally `crate`, `self` and `super`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+enum ImportBucket {
+    Std,
+    External,
+    Crate,
+}
+
+impl ImportBucket {
+    fn of(ident: &str) -> Self {
+        match ident {
+            "std" | "core" | "alloc" | "proc_macro" | "test" => Self::Std,
+            "crate" | "self" | "super" => Self::Crate,
+            _ => Self::External,
+        }
+    }
+}
+
+/// An import flattened out of a use tree, e.g. `std::fmt::Debug as Dbg`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct UseLeaf {
+    item: syn::ItemUse,
+    segments: Vec<String>,
+    rename: Option<String>,
+    is_glob: bool,
+}
+
+impl UseLeaf {
+    /// Use items can only be merged when they share attributes, visibility and leading colon.
+    fn statement_key(&self) -> String {
+        let attrs = self.item.attrs.iter()
+            .map(|attr| attr.to_token_stream().to_string())
+            .join(" ");
+        format!("{} {} {}", attrs, self.item.vis.to_token_stream(), self.item.leading_colon.is_some())
+    }
+
+    fn to_leaf(&self) -> (Path, Option<String>, bool) {
+        (UseItem::path_of(&self.segments), self.rename.clone(), self.is_glob)
+    }
+
+    fn is_shadowed_by(&self, glob: &UseLeaf) -> bool {
+        glob.is_glob && !self.is_glob && self.rename.is_none()
+            && self.segments.len() == glob.segments.len() + 1
+            && self.segments.starts_with(&glob.segments)
+            && self.statement_key() == glob.statement_key()
+    }
+}
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct UseItem(pub syn::ItemUse);
 
 impl UseItem {
+    fn prefix_segments(path_prefix: &Path) -> Vec<String> {
+        path_prefix.iter()
+            .map(|segment| segment.ident.to_string())
+            .collect()
+    }
+
+    /// Whether every import of the tree starts with `path_prefix`, e.g. `use std::{fs, io::*};`
+    /// starts with `std`.
     pub fn start_with(&self, path_prefix: &Path) -> bool {
-        fn walk(tree: &UseTree, path_segments: &mut VecDeque<String>) -> bool {
+        let prefix_segments = Self::prefix_segments(path_prefix);
+        let leaves = self.use_leaves();
+        !leaves.is_empty() && leaves.iter().all(|leaf| leaf.segments.starts_with(&prefix_segments))
+    }
+
+    /// Remove the imports starting with `path_prefix`, splitting groups when only some of their
+    /// members match. Returns whether an import has been removed.
+    pub fn remove_leaves_starting_with(&mut self, path_prefix: &Path) -> bool {
+        let prefix_segments = Self::prefix_segments(path_prefix);
+        let removed_leaves = self.retain_leaves(|leaf| !leaf.segments.starts_with(&prefix_segments));
+        !removed_leaves.is_empty()
+    }
+
+    /// Remove `path_prefix` from every import starting with it, always keeping the imported
+    /// segment, e.g. `use std::{path::PathBuf, fs::File};` trimmed of `std::path` becomes
+    /// `use {PathBuf, std::fs::File};`. Returns whether an import has been trimmed.
+    pub fn trim_path_prefix(&mut self, path_prefix: &Path) -> bool {
+        let prefix_segments = Self::prefix_segments(path_prefix);
+        let mut leaves = self.use_leaves();
+        let mut has_been_trimmed = false;
+        for leaf in leaves.iter_mut() {
+            if prefix_segments.is_empty() || !leaf.segments.starts_with(&prefix_segments) {
+                continue;
+            }
+            let count = match leaf.is_glob {
+                true if leaf.segments.len() == prefix_segments.len() => continue,
+                true => prefix_segments.len(),
+                false => prefix_segments.len().min(leaf.segments.len() - 1),
+            };
+            if count == 0 {
+                continue;
+            }
+            leaf.segments.drain(..count);
+            has_been_trimmed = true;
+        }
+        if has_been_trimmed {
+            self.set_leaves(&leaves);
+        }
+        has_been_trimmed
+    }
+
+    /// Rename the first segment of every path of the tree, e.g. `crate` into `super`.
+    pub fn rename_root(&mut self, from: &str, to: &str) {
+        fn walk(tree: &mut UseTree, from: &str, to: &str) {
             match tree {
-                UseTree::Path(value) => {
-                    let path_segment = path_segments.pop_front().unwrap();
-                    if value.ident.to_string() != path_segment && path_segments.is_empty() == false {
-                        return false;
-                    } else if value.ident.to_string() == path_segment && path_segments.is_empty() {
-                        return true;
+                UseTree::Path(value) if value.ident == from => value.ident = create_ident(to),
+                UseTree::Name(value) if value.ident == from => value.ident = create_ident(to),
+                UseTree::Rename(value) if value.ident == from => value.ident = create_ident(to),
+                UseTree::Group(value) => {
+                    for tree in value.items.iter_mut() {
+                        walk(tree, from, to);
                     }
-                    walk(&value.tree, path_segments)
                 }
-                UseTree::Name(value) => {
-                    if path_segments.is_empty() {
-                        return false;
-                    }
-                    let path_segment = path_segments.pop_front().unwrap();
-                    if value.ident.to_string() == path_segment && path_segments.is_empty() {
-                        return true;
-                    }
-                    false
+                _ => {}
+            }
+        }
+        if self.0.leading_colon.is_some() {
+            return;
+        }
+        walk(&mut self.0.tree, from, to);
+    }
+
+    fn use_leaves(&self) -> Vec<UseLeaf> {
+        fn walk(item: &syn::ItemUse, tree: &UseTree, prefix: &mut Vec<String>, leaves: &mut Vec<UseLeaf>) {
+            let mut push = |segments: Vec<String>, rename: Option<String>, is_glob: bool| {
+                leaves.push(UseLeaf {
+                    item: item.clone(),
+                    segments,
+                    rename,
+                    is_glob,
+                })
+            };
+            match tree {
+                UseTree::Path(value) => {
+                    prefix.push(value.ident.to_string());
+                    walk(item, &value.tree, prefix, leaves);
+                    prefix.pop();
                 }
-                _ => {
-                    false
+                UseTree::Name(value) if value.ident == "self" => push(prefix.clone(), None, false),
+                UseTree::Name(value) => push([prefix.clone(), vec![value.ident.to_string()]].concat(), None, false),
+                UseTree::Rename(value) if value.ident == "self" => {
+                    push(prefix.clone(), Some(value.rename.to_string()), false)
+                }
+                UseTree::Rename(value) => {
+                    push([prefix.clone(), vec![value.ident.to_string()]].concat(), Some(value.rename.to_string()), false)
+                }
+                UseTree::Glob(_) => push(prefix.clone(), None, true),
+                UseTree::Group(value) => {
+                    for tree in value.items.iter() {
+                        walk(item, tree, prefix, leaves);
+                    }
                 }
             }
         }
-        let mut path_segments = path_prefix.iter()
-            .map(|segment| segment.ident.to_string())
-            .collect::<VecDeque<_>>();
-        walk(&self.0.tree, &mut path_segments)
+        let mut leaves = Vec::new();
+        walk(&self.0, &self.0.tree, &mut Vec::new(), &mut leaves);
+        leaves.retain(|leaf| !leaf.segments.is_empty());
+        leaves
     }
 
-    pub fn trim_path_prefix(&mut self, path_prefix: &Path) -> bool {
-        fn walk(tree: &UseTree, path_segments: &mut VecDeque<String>) -> Option<UseTree> {
+    fn bound_ident(leaf: &UseLeaf) -> String {
+        leaf.rename.clone()
+            .unwrap_or(leaf.segments.last().unwrap().clone())
+    }
+
+    fn path_of(segments: &[String]) -> Path {
+        let mut path = Path::new(&segments[0]);
+        for segment in segments[1..].iter() {
+            path.join(segment);
+        }
+        path
+    }
+
+    fn set_leaves(&mut self, leaves: &[UseLeaf]) {
+        let leaves = leaves.iter()
+            .map(UseLeaf::to_leaf)
+            .collect::<Vec<_>>();
+        self.0.tree = create_use_leaves_tree(&leaves);
+    }
+
+    /// Every import of the tree as its path, alias and whether it is a glob, e.g.
+    /// `use std::{fmt::{self, Debug as Dbg}, io::*};` gives `(std::fmt, None, false)`,
+    /// `(std::fmt::Debug, Some("Dbg"), false)` and `(std::io, None, true)`.
+    pub fn leaves(&self) -> Vec<(Path, Option<String>, bool)> {
+        self.use_leaves().iter()
+            .map(UseLeaf::to_leaf)
+            .collect()
+    }
+
+    /// Build a private use item with the minimal tree importing `leaves`, see [`UseItem::leaves`].
+    pub fn from_leaves(leaves: &[(Path, Option<String>, bool)]) -> Self {
+        let mut item = create_use(&Path::new("self"));
+        item.tree = create_use_leaves_tree(leaves);
+        Self(item)
+    }
+
+    /// Keep the imports satisfying `filter`, rebuilding the tree when some are removed. Returns
+    /// the removed imports.
+    fn retain_leaves(&mut self, filter: impl FnMut(&UseLeaf) -> bool) -> Vec<UseLeaf> {
+        let (keep, remove): (Vec<UseLeaf>, Vec<UseLeaf>) = self.use_leaves().into_iter()
+            .partition(filter);
+        if !remove.is_empty() {
+            self.set_leaves(&keep);
+        }
+        remove
+    }
+
+    /// Whether the use item does not import anything, e.g. `use std::{};`.
+    pub fn is_empty(&self) -> bool {
+        self.use_leaves().is_empty()
+    }
+
+    /// Remove the imports whose bound ident (the rename or the last segment) does not satisfy
+    /// `filter`; globs and `as _` imports are always kept. Returns the removed imports as their
+    /// bound ident and path.
+    pub fn retain_imports(&mut self, mut filter: impl FnMut(&str) -> bool) -> Vec<(String, Path)> {
+        let removed_leaves = self.retain_leaves(|leaf| {
+            leaf.is_glob || leaf.rename.as_deref() == Some("_") || filter(&Self::bound_ident(leaf))
+        });
+        removed_leaves.iter()
+            .map(|leaf| (Self::bound_ident(leaf), Self::path_of(&leaf.segments)))
+            .collect()
+    }
+
+    /// Flatten `items` to their imports, drop duplicated imports and the ones already brought by
+    /// a glob, then regroup them into nested use trees following `granularity`. Use items are
+    /// ordered by bucket (`std`, external crates, then `crate`, `self` and `super`).
+    pub fn normalize(items: &[UseItem], granularity: ImportsGranularity) -> Vec<UseItem> {
+        let leaves = items.iter()
+            .flat_map(|item| item.use_leaves())
+            .unique_by(|leaf| (leaf.statement_key(), leaf.segments.clone(), leaf.rename.clone(), leaf.is_glob))
+            .collect::<Vec<_>>();
+        let leaves = leaves.iter()
+            .filter(|leaf| !leaves.iter().any(|glob| leaf.is_shadowed_by(glob)))
+            .collect::<Vec<_>>();
+        let mut statements: Vec<(String, Vec<&UseLeaf>)> = Vec::new();
+        for leaf in leaves {
+            let module_segments = match granularity {
+                ImportsGranularity::Crate => leaf.segments[..1].to_vec(),
+                ImportsGranularity::Module if leaf.is_glob => leaf.segments.clone(),
+                ImportsGranularity::Module => leaf.segments[..leaf.segments.len() - 1].to_vec(),
+                ImportsGranularity::Item => leaf.segments.iter()
+                    .cloned()
+                    .chain(leaf.rename.iter().map(|rename| format!(" as {}", rename)))
+                    .chain(leaf.is_glob.then(|| "*".to_string()))
+                    .collect(),
+                ImportsGranularity::One => vec![],
+            };
+            let key = format!("{} {}", leaf.statement_key(), module_segments.join("::"));
+            match statements.iter_mut().find(|(statement_key, _)| *statement_key == key) {
+                Some((_, leaves)) => leaves.push(leaf),
+                None => statements.push((key, vec![leaf])),
+            }
+        }
+        statements.into_iter()
+            .map(|(_, leaves)| {
+                let mut item = UseItem(leaves[0].item.clone());
+                item.set_leaves(&leaves.iter().map(|leaf| (*leaf).clone()).collect::<Vec<_>>());
+                let bucket = leaves.iter()
+                    .map(|leaf| ImportBucket::of(&leaf.segments[0]))
+                    .min()
+                    .unwrap();
+                (bucket, item)
+            })
+            .sorted_by(|(a_bucket, a), (b_bucket, b)| {
+                let a_is_inherited = matches!(a.0.vis, Visibility::Inherited);
+                let b_is_inherited = matches!(b.0.vis, Visibility::Inherited);
+                a_bucket.cmp(b_bucket)
+                    .then_with(|| b_is_inherited.cmp(&a_is_inherited))
+                    .then_with(|| a.to_string().cmp(&b.to_string()))
+            })
+            .map(|(_, item)| item)
+            .collect()
+    }
+
+    pub fn walk(&mut self, context: &mut Context) {
+        fn walk(tree: &mut UseTree, context: &mut Context) {
             match tree {
                 UseTree::Path(value) => {
-                    let path_segment = path_segments.pop_front().unwrap();
-                    if value.ident.to_string() != path_segment && path_segments.is_empty() == false {
-                        return None;
-                    } else if value.ident.to_string() == path_segment && path_segments.is_empty() {
-                        return Some(*value.tree.clone());
-                    }
-                    walk(&value.tree, path_segments)
+                    context.predict_ident(&mut value.ident);
+                    walk(&mut value.tree, context);
                 }
                 UseTree::Name(value) => {
-                    if path_segments.is_empty() {
-                        return None;
-                    }
-                    let path_segment = path_segments.pop_front().unwrap();
-                    if value.ident.to_string() == path_segment && path_segments.is_empty() {
-                        return Some(tree.clone());
+                    context.predict_ident(&mut value.ident);
+                }
+                UseTree::Rename(value) => {
+                    context.predict_ident(&mut value.ident);
+                    context.predict_ident(&mut value.rename);
+                }
+                UseTree::Glob(_) => {}
+                UseTree::Group(value) => {
+                    for tree in value.items.iter_mut() {
+                        walk(tree, context);
                     }
-                    None
                 }
-                _ => None,
             }
         }
-        let mut path_segments = path_prefix.iter()
-            .map(|segment| segment.ident.to_string())
-            .collect::<VecDeque<_>>();
-        let tree = match walk(&self.0.tree, &mut path_segments) {
-            Some(value) => value,
-            None => return false
-        };
-        self.0.tree = tree;
-        true
+        walk(&mut self.0.tree, context);
     }
 
     pub fn to_string(&self) -> String {
@@ -0,0 +1,173 @@
+use crate::items::use_item::{ImportsGranularity, UseItem};
+use crate::utils::path::Path;
+use pretty_assertions::assert_eq;
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use rstest::rstest;
+use syn::parse::Parser;
+
+fn use_items(quote: TokenStream) -> Vec<UseItem> {
+    syn::Block::parse_within.parse2(quote)
+        .unwrap()
+        .into_iter()
+        .map(|stmt| match stmt {
+            syn::Stmt::Item(syn::Item::Use(value)) => UseItem(value),
+            _ => panic!("Expected use item"),
+        })
+        .collect()
+}
+
+fn to_string(items: Vec<UseItem>) -> String {
+    items.iter()
+        .map(|item| item.0.to_token_stream().to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn imports() -> TokenStream {
+    quote! {
+        use crate::models::User;
+        use std::fmt::Display;
+        use std::fmt::{Debug, Display};
+        use serde::Serialize;
+        use std::io::*;
+        use std::io::Read;
+        use std::fmt::{self, Write as FmtWrite};
+        use crate::models::{Group, User};
+        pub use crate::models::Role;
+    }
+}
+
+#[rstest]
+#[case::crate_(ImportsGranularity::Crate, quote! {
+    use std::{fmt::{self, Debug, Display, Write as FmtWrite}, io::*};
+    use serde::Serialize;
+    use crate::models::{Group, User};
+    pub use crate::models::Role;
+})]
+#[case::module(ImportsGranularity::Module, quote! {
+    use std::fmt;
+    use std::fmt::{Debug, Display, Write as FmtWrite};
+    use std::io::*;
+    use serde::Serialize;
+    use crate::models::{Group, User};
+    pub use crate::models::Role;
+})]
+#[case::item(ImportsGranularity::Item, quote! {
+    use std::fmt;
+    use std::fmt::Debug;
+    use std::fmt::Display;
+    use std::fmt::Write as FmtWrite;
+    use std::io::*;
+    use serde::Serialize;
+    use crate::models::Group;
+    use crate::models::User;
+    pub use crate::models::Role;
+})]
+#[case::one(ImportsGranularity::One, quote! {
+    use {crate::models::{Group, User}, serde::Serialize, std::{fmt::{self, Debug, Display, Write as FmtWrite}, io::*}};
+    pub use crate::models::Role;
+})]
+fn normalize(#[case] granularity: ImportsGranularity, #[case] expected_quote: TokenStream) {
+    let items = UseItem::normalize(&use_items(imports()), granularity);
+    assert_eq!(to_string(use_items(expected_quote)), to_string(items));
+}
+
+fn path(value: &str) -> Path {
+    let mut segments = value.split("::");
+    let mut path = Path::new(segments.next().unwrap());
+    for segment in segments {
+        path.join(segment);
+    }
+    path
+}
+
+#[rstest]
+#[case::name(quote!(use std::path::PathBuf;), "std::path", true)]
+#[case::whole_path(quote!(use std::path::PathBuf;), "std::path::PathBuf", true)]
+#[case::longer_prefix(quote!(use std::path;), "std::path::PathBuf", false)]
+#[case::mismatch(quote!(use std::path::PathBuf;), "std::fs", false)]
+#[case::group(quote!(use std::{fs::File, path::PathBuf};), "std", true)]
+#[case::partial_group(quote!(use std::{fs::File, path::PathBuf};), "std::path", false)]
+#[case::rename(quote!(use std::path::PathBuf as Buf;), "std::path", true)]
+#[case::glob(quote!(use std::path::*;), "std::path", true)]
+fn start_with(#[case] quote: TokenStream, #[case] prefix: &str, #[case] expected: bool) {
+    let item = use_items(quote).remove(0);
+    assert_eq!(expected, item.start_with(&path(prefix)));
+}
+
+#[rstest]
+#[case::name(quote!(use std::path::PathBuf;), "std::path", quote!(use PathBuf;), true)]
+#[case::whole_path(quote!(use std::path::PathBuf;), "std::path::PathBuf", quote!(use PathBuf;), true)]
+#[case::longer_prefix(quote!(use std::path;), "std::path::PathBuf", quote!(use std::path;), false)]
+#[case::mismatch(quote!(use std::path::PathBuf;), "std::fs", quote!(use std::path::PathBuf;), false)]
+#[case::group(quote!(use std::path::{Path, PathBuf};), "std::path", quote!(use {Path, PathBuf};), true)]
+#[case::split_group(
+    quote!(use std::{path::{self, PathBuf}, fs::File};),
+    "std::path",
+    quote!(use {PathBuf, path, std::fs::File};),
+    true
+)]
+#[case::split_group_without_self(
+    quote!(use std::{path::PathBuf, fs::File};),
+    "std::path",
+    quote!(use {PathBuf, std::fs::File};),
+    true
+)]
+#[case::rename(quote!(use std::path::PathBuf as Buf;), "std::path", quote!(use PathBuf as Buf;), true)]
+#[case::glob(quote!(use std::path::*;), "std", quote!(use path::*;), true)]
+#[case::whole_glob(quote!(use std::path::*;), "std::path", quote!(use std::path::*;), false)]
+fn trim_path_prefix(
+    #[case] quote: TokenStream,
+    #[case] prefix: &str,
+    #[case] expected_quote: TokenStream,
+    #[case] expected: bool,
+) {
+    let mut item = use_items(quote).remove(0);
+    assert_eq!(expected, item.trim_path_prefix(&path(prefix)));
+    assert_eq!(to_string(use_items(expected_quote)), to_string(vec![item]));
+}
+
+#[rstest]
+fn remove_leaves_starting_with() {
+    let mut item = use_items(quote!(use std::{fs::File, path::{Path, PathBuf}};)).remove(0);
+    assert_eq!(true, item.remove_leaves_starting_with(&path("std::path")));
+    assert_eq!(to_string(use_items(quote!(use std::fs::File;))), to_string(vec![item.clone()]));
+    assert_eq!(true, item.remove_leaves_starting_with(&path("std")));
+    assert_eq!(true, item.is_empty());
+}
+
+#[rstest]
+fn leaves() {
+    let item = use_items(quote!(use std::{fmt::{self, Debug as Dbg}, io::*, path::PathBuf};)).remove(0);
+    let expected_leaves = vec![
+        (path("std::fmt"), None, false),
+        (path("std::fmt::Debug"), Some("Dbg".to_string()), false),
+        (path("std::io"), None, true),
+        (path("std::path::PathBuf"), None, false),
+    ];
+    assert_eq!(expected_leaves, item.leaves());
+}
+
+#[rstest]
+#[case::name(vec![(path("std::path::PathBuf"), None, false)], quote!(use std::path::PathBuf;))]
+#[case::rename(vec![(path("std::path::PathBuf"), Some("Buf".to_string()), false)], quote!(use std::path::PathBuf as Buf;))]
+#[case::glob(vec![(path("std::io"), None, true)], quote!(use std::io::*;))]
+#[case::group(
+    vec![
+        (path("std::path::PathBuf"), None, false),
+        (path("std::fmt::Debug"), Some("Dbg".to_string()), false),
+        (path("std::fmt"), None, false),
+        (path("std::io"), None, true),
+        (path("std::path::PathBuf"), None, false),
+    ],
+    quote!(use std::{fmt::{self, Debug as Dbg}, io::*, path::PathBuf};)
+)]
+#[case::roots(
+    vec![(path("crate::Foo"), None, false), (path("std::fmt"), None, false)],
+    quote!(use {crate::Foo, std::fmt};)
+)]
+fn from_leaves(#[case] leaves: Vec<(Path, Option<String>, bool)>, #[case] expected_quote: TokenStream) {
+    let item = UseItem::from_leaves(&leaves);
+    assert_eq!(to_string(use_items(expected_quote)), to_string(vec![item]));
+}
@@ -1,8 +1,11 @@
 use crate::items::module_item::ModuleItem;
+use crate::items::source_file::SourceFile;
 use crate::items::struct_item::StructItem;
 use crate::utils::create_ident;
+use crate::utils::parsing::TokenStreamExt;
 use crate::utils::path::Path;
 use crate::utils::punctuated::PunctuatedExt;
+use proc_macro2::TokenStream;
 use quote::ToTokens;
 use rstest::fixture;
 use syn::punctuated::Punctuated;
@@ -14,6 +17,19 @@ pub fn module() -> ModuleItem {
     ModuleItem::new("test_module")
 }
 
+/// Source file made of one module per `(name, quote)`, the module file names being their names.
+pub fn source_file(modules: Vec<(&str, TokenStream)>) -> SourceFile {
+    let modules = modules.into_iter()
+        .map(|(name, quote)| {
+            let mut module = quote.parse().modules.remove(0);
+            module.name = name.to_string();
+            module.file_name = name.to_string();
+            module
+        })
+        .collect();
+    SourceFile::new(vec![], modules)
+}
+
 #[fixture]
 pub fn required_field() -> syn::Field {
     syn::Field {
@@ -0,0 +1,323 @@
+use crate::error::Error;
+use crate::utils::path::Path;
+use crate::utils::statement::Expr;
+use proc_macro2::Span;
+use syn::punctuated::Punctuated;
+use syn::{Arm, BinOp, Block, ExprArray, ExprAwait, ExprBinary, ExprCast, ExprForLoop, ExprIndex, ExprLit, ExprLoop, ExprMatch, ExprParen, ExprPath, ExprRange, ExprReference, ExprTry, ExprTuple, ExprUnary, ExprWhile, Lit, LitBool, LitChar, LitFloat, LitInt, LitStr, Pat, RangeLimits, Stmt, Token, Type, UnOp};
+
+impl From<syn::Expr> for Expr {
+    fn from(value: syn::Expr) -> Self {
+        Expr::Expr(value)
+    }
+}
+
+impl From<Stmt> for Expr {
+    fn from(value: Stmt) -> Self {
+        Expr::Stmt(value)
+    }
+}
+
+impl From<Path> for Expr {
+    fn from(value: Path) -> Self {
+        Expr::Path(value)
+    }
+}
+
+/// Builder of `syn::Expr` trees. Operands are parenthesized when their precedence requires it,
+/// e.g. `(a + b) * c`.
+pub struct ExprBuilder;
+
+impl ExprBuilder {
+    pub fn lit(lit: Lit) -> syn::Expr {
+        syn::Expr::Lit(ExprLit {
+            attrs: vec![],
+            lit,
+        })
+    }
+
+    pub fn lit_str(value: &str) -> syn::Expr {
+        Self::lit(Lit::Str(LitStr::new(value, Span::call_site())))
+    }
+
+    /// Integer literal, with its suffix if any, e.g. `1`, `-1` or `1u8`.
+    pub fn lit_int(value: &str) -> syn::Expr {
+        let lit = Self::lit(Lit::Int(LitInt::new(value.trim_start_matches('-'), Span::call_site())));
+        match value.starts_with('-') {
+            true => Self::unary_with(UnOp::Neg(Default::default()), lit),
+            false => lit,
+        }
+    }
+
+    /// Float literal, with its suffix if any, e.g. `1.5`, `-1.5` or `1f32`.
+    pub fn lit_float(value: &str) -> syn::Expr {
+        let lit = Self::lit(Lit::Float(LitFloat::new(value.trim_start_matches('-'), Span::call_site())));
+        match value.starts_with('-') {
+            true => Self::unary_with(UnOp::Neg(Default::default()), lit),
+            false => lit,
+        }
+    }
+
+    pub fn lit_bool(value: bool) -> syn::Expr {
+        Self::lit(Lit::Bool(LitBool::new(value, Span::call_site())))
+    }
+
+    pub fn lit_char(value: char) -> syn::Expr {
+        Self::lit(Lit::Char(LitChar::new(value, Span::call_site())))
+    }
+
+    pub fn path(path: Path) -> syn::Expr {
+        syn::Expr::Path(ExprPath {
+            attrs: vec![],
+            qself: None,
+            path: path.to_syn_path(),
+        })
+    }
+
+    pub fn paren(expr: impl Into<Expr>) -> syn::Expr {
+        syn::Expr::Paren(ExprParen {
+            attrs: vec![],
+            paren_token: Default::default(),
+            expr: Box::new(expr.into().to_expr()),
+        })
+    }
+
+    /// Binding power of a binary operator, the higher the tighter.
+    fn precedence(operator: &BinOp) -> u8 {
+        match operator {
+            BinOp::Mul(_) | BinOp::Div(_) | BinOp::Rem(_) => 10,
+            BinOp::Add(_) | BinOp::Sub(_) => 9,
+            BinOp::Shl(_) | BinOp::Shr(_) => 8,
+            BinOp::BitAnd(_) => 7,
+            BinOp::BitXor(_) => 6,
+            BinOp::BitOr(_) => 5,
+            BinOp::Eq(_) | BinOp::Ne(_) | BinOp::Lt(_) | BinOp::Le(_) | BinOp::Gt(_) | BinOp::Ge(_) => 4,
+            BinOp::And(_) => 3,
+            BinOp::Or(_) => 2,
+            _ => 1,
+        }
+    }
+
+    /// Parenthesize operands binding looser than unary operators and casts.
+    fn unary_operand(expr: Expr) -> syn::Expr {
+        let expr = expr.to_expr();
+        match expr {
+            syn::Expr::Binary(_) | syn::Expr::Range(_) | syn::Expr::Assign(_) | syn::Expr::Closure(_) => Self::paren(expr),
+            _ => expr,
+        }
+    }
+
+    /// Parenthesize operands binding looser than postfix operators (`?`, `.await`, `[]`).
+    fn postfix_operand(expr: Expr) -> syn::Expr {
+        let expr = Self::unary_operand(expr);
+        match expr {
+            syn::Expr::Unary(_) | syn::Expr::Reference(_) | syn::Expr::Cast(_) => Self::paren(expr),
+            _ => expr,
+        }
+    }
+
+    fn binary_operand(expr: Expr, precedence: u8, is_right: bool) -> syn::Expr {
+        let expr = expr.to_expr();
+        let needs_paren = match &expr {
+            syn::Expr::Binary(value) if is_right => Self::precedence(&value.op) <= precedence,
+            syn::Expr::Binary(value) => Self::precedence(&value.op) < precedence,
+            syn::Expr::Range(_) | syn::Expr::Assign(_) | syn::Expr::Closure(_) => true,
+            _ => false,
+        };
+        match needs_paren {
+            true => Self::paren(expr),
+            false => expr,
+        }
+    }
+
+    /// Binary operation, e.g. `binary(a, "+", b)`. Fails on an unknown operator.
+    pub fn binary(left: impl Into<Expr>, operator: &str, right: impl Into<Expr>) -> Result<syn::Expr, Error> {
+        match syn::parse_str::<BinOp>(operator) {
+            Ok(operator) => Ok(Self::binary_with(left, operator, right)),
+            Err(_) => Err(Error {
+                description: format!("Unsupported binary operator '{}'", operator),
+            }),
+        }
+    }
+
+    pub fn binary_with(left: impl Into<Expr>, operator: BinOp, right: impl Into<Expr>) -> syn::Expr {
+        let precedence = Self::precedence(&operator);
+        syn::Expr::Binary(ExprBinary {
+            attrs: vec![],
+            left: Box::new(Self::binary_operand(left.into(), precedence, false)),
+            op: operator,
+            right: Box::new(Self::binary_operand(right.into(), precedence, true)),
+        })
+    }
+
+    /// Unary operation (`!`, `-` or `*`). Fails on an unknown operator.
+    pub fn unary(operator: &str, expr: impl Into<Expr>) -> Result<syn::Expr, Error> {
+        match syn::parse_str::<UnOp>(operator) {
+            Ok(operator) => Ok(Self::unary_with(operator, expr)),
+            Err(_) => Err(Error {
+                description: format!("Unsupported unary operator '{}'", operator),
+            }),
+        }
+    }
+
+    pub fn unary_with(operator: UnOp, expr: impl Into<Expr>) -> syn::Expr {
+        syn::Expr::Unary(ExprUnary {
+            attrs: vec![],
+            op: operator,
+            expr: Box::new(Self::unary_operand(expr.into())),
+        })
+    }
+
+    pub fn reference(expr: impl Into<Expr>, is_mutable: bool) -> syn::Expr {
+        syn::Expr::Reference(ExprReference {
+            attrs: vec![],
+            and_token: Default::default(),
+            mutability: match is_mutable {
+                true => Some(Default::default()),
+                false => None,
+            },
+            expr: Box::new(Self::unary_operand(expr.into())),
+        })
+    }
+
+    pub fn cast(expr: impl Into<Expr>, type_: Type) -> syn::Expr {
+        syn::Expr::Cast(ExprCast {
+            attrs: vec![],
+            expr: Box::new(Self::unary_operand(expr.into())),
+            as_token: Default::default(),
+            ty: Box::new(type_),
+        })
+    }
+
+    /// `expr?`
+    pub fn try_(expr: impl Into<Expr>) -> syn::Expr {
+        syn::Expr::Try(ExprTry {
+            attrs: vec![],
+            expr: Box::new(Self::postfix_operand(expr.into())),
+            question_token: Default::default(),
+        })
+    }
+
+    /// `expr.await`
+    pub fn await_(expr: impl Into<Expr>) -> syn::Expr {
+        syn::Expr::Await(ExprAwait {
+            attrs: vec![],
+            base: Box::new(Self::postfix_operand(expr.into())),
+            dot_token: Default::default(),
+            await_token: Default::default(),
+        })
+    }
+
+    /// `expr[index]`
+    pub fn index(expr: impl Into<Expr>, index: impl Into<Expr>) -> syn::Expr {
+        syn::Expr::Index(ExprIndex {
+            attrs: vec![],
+            expr: Box::new(Self::postfix_operand(expr.into())),
+            bracket_token: Default::default(),
+            index: Box::new(index.into().to_expr()),
+        })
+    }
+
+    /// `start..end` or `start..=end`, both bounds being optional.
+    pub fn range(start: Option<Expr>, end: Option<Expr>, is_inclusive: bool) -> syn::Expr {
+        let bound = |expr: Expr| Box::new(Self::binary_operand(expr, 1, false));
+        syn::Expr::Range(ExprRange {
+            attrs: vec![],
+            start: start.map(bound),
+            limits: match is_inclusive {
+                true => RangeLimits::Closed(Default::default()),
+                false => RangeLimits::HalfOpen(Default::default()),
+            },
+            end: end.map(bound),
+        })
+    }
+
+    /// Tuple of `elements`, the unit value when empty.
+    pub fn tuple(elements: Vec<Expr>) -> syn::Expr {
+        let mut elements = elements.iter()
+            .map(|element| element.to_expr())
+            .collect::<Punctuated<syn::Expr, Token![,]>>();
+        if elements.len() == 1 {
+            elements.push_punct(Default::default());
+        }
+        syn::Expr::Tuple(ExprTuple {
+            attrs: vec![],
+            paren_token: Default::default(),
+            elems: elements,
+        })
+    }
+
+    pub fn array(elements: Vec<Expr>) -> syn::Expr {
+        syn::Expr::Array(ExprArray {
+            attrs: vec![],
+            bracket_token: Default::default(),
+            elems: elements.iter()
+                .map(|element| element.to_expr())
+                .collect(),
+        })
+    }
+
+    fn block(statements: Vec<Stmt>) -> Block {
+        Block {
+            brace_token: Default::default(),
+            stmts: statements,
+        }
+    }
+
+    /// Match arm `pattern if guard => body,`, see [`crate::utils::pattern::PatternBuilder`] for
+    /// the patterns.
+    pub fn arm(pattern: Pat, guard: Option<Expr>, body: impl Into<Expr>) -> Arm {
+        Arm {
+            attrs: vec![],
+            pat: pattern,
+            guard: guard.map(|guard| (Default::default(), Box::new(guard.to_expr()))),
+            fat_arrow_token: Default::default(),
+            body: Box::new(body.into().to_expr()),
+            comma: Some(Default::default()),
+        }
+    }
+
+    /// `match expr { arms }`, the arms being built with [`ExprBuilder::arm`].
+    pub fn match_(expr: impl Into<Expr>, arms: Vec<Arm>) -> syn::Expr {
+        syn::Expr::Match(ExprMatch {
+            attrs: vec![],
+            match_token: Default::default(),
+            expr: Box::new(expr.into().to_expr()),
+            brace_token: Default::default(),
+            arms,
+        })
+    }
+
+    /// `for pattern in iterable { body }`
+    pub fn for_loop(pattern: Pat, iterable: impl Into<Expr>, body: Vec<Stmt>) -> syn::Expr {
+        syn::Expr::ForLoop(ExprForLoop {
+            attrs: vec![],
+            label: None,
+            for_token: Default::default(),
+            pat: Box::new(pattern),
+            in_token: Default::default(),
+            expr: Box::new(iterable.into().to_expr()),
+            body: Self::block(body),
+        })
+    }
+
+    /// `while condition { body }`
+    pub fn while_loop(condition: impl Into<Expr>, body: Vec<Stmt>) -> syn::Expr {
+        syn::Expr::While(ExprWhile {
+            attrs: vec![],
+            label: None,
+            while_token: Default::default(),
+            cond: Box::new(condition.into().to_expr()),
+            body: Self::block(body),
+        })
+    }
+
+    /// `loop { body }`
+    pub fn loop_(body: Vec<Stmt>) -> syn::Expr {
+        syn::Expr::Loop(ExprLoop {
+            attrs: vec![],
+            label: None,
+            loop_token: Default::default(),
+            body: Self::block(body),
+        })
+    }
+}
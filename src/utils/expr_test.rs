@@ -0,0 +1,103 @@
+use crate::utils::expr::ExprBuilder;
+use crate::utils::path::Path;
+use crate::utils::statement::{Expr, Statement};
+use pretty_assertions::assert_eq;
+use quote::ToTokens;
+use rstest::rstest;
+
+fn path(value: &str) -> Expr {
+    Expr::Path(Path::parse(value).unwrap())
+}
+
+fn assert_expr(expected_expr: syn::Expr, expr: syn::Expr) {
+    assert_eq!(expected_expr.to_token_stream().to_string(), expr.to_token_stream().to_string());
+}
+
+#[rstest]
+fn literals() {
+    assert_expr(syn::parse_quote!("value"), ExprBuilder::lit_str("value"));
+    assert_expr(syn::parse_quote!(1u8), ExprBuilder::lit_int("1u8"));
+    assert_expr(syn::parse_quote!(-1), ExprBuilder::lit_int("-1"));
+    assert_expr(syn::parse_quote!(1.5), ExprBuilder::lit_float("1.5"));
+    assert_expr(syn::parse_quote!(-1.5f32), ExprBuilder::lit_float("-1.5f32"));
+    assert_expr(syn::parse_quote!(true), ExprBuilder::lit_bool(true));
+    assert_expr(syn::parse_quote!('a'), ExprBuilder::lit_char('a'));
+}
+
+#[rstest]
+fn operations() {
+    let sum = ExprBuilder::binary(path("a"), "+", path("b")).unwrap();
+    assert_expr(syn::parse_quote!(a + b), sum.clone());
+    assert_expr(syn::parse_quote!((a + b) * c), ExprBuilder::binary(sum.clone(), "*", path("c")).unwrap());
+    assert_expr(syn::parse_quote!(c - (a + b)), ExprBuilder::binary(path("c"), "-", sum.clone()).unwrap());
+    assert_expr(syn::parse_quote!(a + b - c), ExprBuilder::binary(sum.clone(), "-", path("c")).unwrap());
+    assert_expr(syn::parse_quote!(a + b == c && d), ExprBuilder::binary(ExprBuilder::binary(sum.clone(), "==", path("c")).unwrap(), "&&", path("d")).unwrap());
+    assert_expr(syn::parse_quote!(x += a + b), ExprBuilder::binary(path("x"), "+=", sum.clone()).unwrap());
+    assert_expr(syn::parse_quote!(!(a + b)), ExprBuilder::unary("!", sum.clone()).unwrap());
+    assert_expr(syn::parse_quote!(*value), ExprBuilder::unary("*", path("value")).unwrap());
+    assert_expr(syn::parse_quote!(&mut value), ExprBuilder::reference(path("value"), true));
+    assert_expr(syn::parse_quote!(&(a + b)), ExprBuilder::reference(sum.clone(), false));
+    assert_expr(syn::parse_quote!((a + b) as u64), ExprBuilder::cast(sum, syn::parse_quote!(u64)));
+}
+
+#[rstest]
+fn unknown_operators() {
+    let error = ExprBuilder::binary(path("a"), "=>", path("b")).unwrap_err();
+    assert_eq!("Unsupported binary operator '=>'", error.description);
+    let error = ExprBuilder::unary("~", path("a")).unwrap_err();
+    assert_eq!("Unsupported unary operator '~'", error.description);
+}
+
+#[rstest]
+fn operations_with_syn_operators() {
+    assert_expr(
+        syn::parse_quote!(a * (b + c)),
+        ExprBuilder::binary_with(path("a"), syn::parse_quote!(*), ExprBuilder::binary_with(path("b"), syn::parse_quote!(+), path("c"))),
+    );
+    assert_expr(syn::parse_quote!(-a), ExprBuilder::unary_with(syn::parse_quote!(-), path("a")));
+}
+
+#[rstest]
+fn postfix_operations() {
+    let call = Statement::call(Path::new("load"), vec![]);
+    assert_expr(syn::parse_quote!(load()?), ExprBuilder::try_(call.clone()));
+    assert_expr(syn::parse_quote!(load().await?), ExprBuilder::try_(ExprBuilder::await_(call)));
+    assert_expr(syn::parse_quote!((*value)?), ExprBuilder::try_(ExprBuilder::unary("*", path("value")).unwrap()));
+    assert_expr(syn::parse_quote!(values[index + 1]), ExprBuilder::index(path("values"), ExprBuilder::binary(path("index"), "+", ExprBuilder::lit_int("1")).unwrap()));
+    assert_expr(syn::parse_quote!((&values)[0]), ExprBuilder::index(ExprBuilder::reference(path("values"), false), ExprBuilder::lit_int("0")));
+}
+
+#[rstest]
+fn ranges() {
+    let zero = || Some(Expr::from(ExprBuilder::lit_int("0")));
+    assert_expr(syn::parse_quote!(0..length), ExprBuilder::range(zero(), Some(path("length")), false));
+    assert_expr(syn::parse_quote!(0..=length - 1), ExprBuilder::range(zero(), Some(Expr::from(ExprBuilder::binary(path("length"), "-", ExprBuilder::lit_int("1")).unwrap())), true));
+    assert_expr(syn::parse_quote!(..), ExprBuilder::range(None, None, false));
+    assert_expr(syn::parse_quote!(values[1..]), ExprBuilder::index(path("values"), ExprBuilder::range(Some(Expr::from(ExprBuilder::lit_int("1"))), None, false)));
+}
+
+#[rstest]
+fn collections() {
+    assert_expr(syn::parse_quote!(()), ExprBuilder::tuple(vec![]));
+    assert_expr(syn::parse_quote!((a,)), ExprBuilder::tuple(vec![path("a")]));
+    assert_expr(syn::parse_quote!((a, 1)), ExprBuilder::tuple(vec![path("a"), Expr::from(ExprBuilder::lit_int("1"))]));
+    assert_expr(syn::parse_quote!([a, b]), ExprBuilder::array(vec![path("a"), path("b")]));
+}
+
+#[rstest]
+fn control_flow() {
+    let body = vec![Statement::call(Path::new("process"), vec![path("value")])];
+    assert_expr(
+        syn::parse_quote!(for value in values { process(value); }),
+        ExprBuilder::for_loop(syn::parse_quote!(value), path("values"), body.clone())
+    );
+    assert_expr(
+        syn::parse_quote!(while !done { process(value); }),
+        ExprBuilder::while_loop(ExprBuilder::unary("!", path("done")).unwrap(), body.clone())
+    );
+    assert_expr(syn::parse_quote!(loop { process(value); }), ExprBuilder::loop_(body));
+    assert_expr(
+        syn::parse_quote!(match value { Some(value) => value, None => 0, }),
+        ExprBuilder::match_(path("value"), vec![syn::parse_quote!(Some(value) => value,), syn::parse_quote!(None => 0,)])
+    );
+}
@@ -1,3 +1,8 @@
+pub mod expr;
+#[cfg(test)]
+#[path = "./expr_test.rs"]
+mod expr_test;
+
 pub mod name_conventions;
 #[cfg(test)]
 #[path = "./naming_conventions_test.rs"]
@@ -5,13 +10,32 @@ mod naming_conventions_test;
 
 pub mod parsing;
 pub mod path;
+#[cfg(test)]
+#[path = "./path_test.rs"]
+mod path_test;
+
+pub mod path_pattern;
+#[cfg(test)]
+#[path = "./path_pattern_test.rs"]
+mod path_pattern_test;
+
+pub mod pattern;
+#[cfg(test)]
+#[path = "./pattern_test.rs"]
+mod pattern_test;
+
 pub mod punctuated;
 pub mod statement;
+pub mod types;
+#[cfg(test)]
+#[path = "./types_test.rs"]
+mod types_test;
 
 use crate::utils::path::Path;
+use std::collections::{BTreeMap, BTreeSet};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
-use syn::{Ident, ItemUse, Token, UseGlob, UseGroup, UseName, UsePath, UseTree, Visibility};
+use syn::{Ident, ItemUse, Token, UseGlob, UseGroup, UseName, UsePath, UseRename, UseTree, Visibility};
 
 #[macro_export]
 macro_rules! debug {
@@ -47,6 +71,118 @@ pub fn create_use_tree(path: &Path) -> UseTree {
     tree
 }
 
+pub fn create_use_rename_tree(path: &Path, rename: &str) -> UseTree {
+    let use_rename = UseRename {
+        ident: path.segments.last().unwrap().ident.clone(),
+        as_token: Default::default(),
+        rename: create_ident(rename),
+    };
+    let mut tree = UseTree::Rename(use_rename);
+    for index in (0..path.segments.len() - 1).rev() {
+        let segment = &path.segments[index];
+        tree = UseTree::Path(UsePath {
+            ident: segment.ident.clone(),
+            colon2_token: Default::default(),
+            tree: Box::new(tree.clone()),
+        })
+    }
+    tree
+}
+
+/// Trie of the imports of a use tree, children and renames being kept sorted.
+#[derive(Debug, Default)]
+struct UseTreeNode {
+    is_leaf: bool,
+    is_glob: bool,
+    renames: BTreeSet<String>,
+    children: BTreeMap<String, UseTreeNode>,
+}
+
+impl UseTreeNode {
+    fn insert(&mut self, segments: &[String], rename: &Option<String>, is_glob: bool) {
+        if let Some((segment, segments)) = segments.split_first() {
+            return self.children.entry(segment.clone())
+                .or_default()
+                .insert(segments, rename, is_glob);
+        }
+        match (rename, is_glob) {
+            (_, true) => self.is_glob = true,
+            (Some(rename), false) => {
+                self.renames.insert(rename.clone());
+            }
+            (None, false) => self.is_leaf = true,
+        }
+    }
+
+    fn trees(&self) -> Vec<UseTree> {
+        let mut trees = Vec::new();
+        if self.is_leaf {
+            trees.push(UseTree::Name(UseName {
+                ident: create_ident("self"),
+            }));
+        }
+        for rename in self.renames.iter() {
+            trees.push(UseTree::Rename(UseRename {
+                ident: create_ident("self"),
+                as_token: Default::default(),
+                rename: create_ident(rename),
+            }));
+        }
+        for (ident, child) in self.children.iter() {
+            trees.push(child.tree(ident));
+        }
+        if self.is_glob {
+            trees.push(UseTree::Glob(UseGlob {
+                star_token: Default::default(),
+            }));
+        }
+        trees
+    }
+
+    fn tree(&self, ident: &str) -> UseTree {
+        if self.children.is_empty() && !self.is_glob && self.renames.len() + self.is_leaf as usize == 1 {
+            return match self.renames.first() {
+                Some(rename) => UseTree::Rename(UseRename {
+                    ident: create_ident(ident),
+                    as_token: Default::default(),
+                    rename: create_ident(rename),
+                }),
+                None => UseTree::Name(UseName {
+                    ident: create_ident(ident),
+                }),
+            };
+        }
+        UseTree::Path(UsePath {
+            ident: create_ident(ident),
+            colon2_token: Default::default(),
+            tree: Box::new(Self::group(self.trees())),
+        })
+    }
+
+    fn group(mut trees: Vec<UseTree>) -> UseTree {
+        match trees.len() {
+            1 => trees.remove(0),
+            _ => UseTree::Group(UseGroup {
+                brace_token: Default::default(),
+                items: trees.into_iter().collect::<Punctuated<_, _>>(),
+            }),
+        }
+    }
+}
+
+/// Build the minimal use tree importing `leaves`, given as their path, alias and whether they are
+/// globs, e.g. `std::fmt` and `std::fmt::Debug` give `std::fmt::{self, Debug}`.
+pub fn create_use_leaves_tree(leaves: &[(Path, Option<String>, bool)]) -> UseTree {
+    let mut root = UseTreeNode::default();
+    for (path, rename, is_glob) in leaves.iter() {
+        let segments = path.iter()
+            .map(|segment| segment.ident.to_string())
+            .collect::<Vec<_>>();
+        root.insert(&segments, rename, *is_glob);
+    }
+    UseTreeNode::group(root.trees())
+}
+
 pub fn create_use_group_tree(path: &Path, names: &Vec<String>) -> UseTree {
     let use_group = UseGroup {
         brace_token: Default::default(),
@@ -1,19 +1,25 @@
 use crate::error::Error;
-use crate::functions;
 use crate::utils::create_ident;
 use crate::utils::punctuated::PunctuatedExt;
-use itertools::Itertools;
+use crate::walkers::path::PathWalker;
+use crate::walkers::type_::TypeWalker;
+use crate::walkers::Context;
+use proc_macro2::{Delimiter, Spacing, Span, TokenStream, TokenTree};
 use quote::ToTokens;
-use std::collections::VecDeque;
+use std::cell::Cell;
+use std::rc::Rc;
 use std::fmt::{Debug, Display, Formatter};
 use std::path::MAIN_SEPARATOR_STR;
 use syn::punctuated::{Iter, IterMut, Punctuated};
 use syn::spanned::Spanned;
-use syn::token::{Comma, PathSep};
-use syn::{AngleBracketedGenericArguments, GenericArgument, Ident, ParenthesizedGenericArguments, PathArguments, PathSegment, ReturnType, Token, Type, TypePath};
+use syn::{AngleBracketedGenericArguments, AssocType, Expr, GenericArgument, Ident, Lifetime, ParenthesizedGenericArguments, PathArguments, PathSegment, ReturnType, Token, TraitBound, TraitBoundModifier, Type, TypePath};
+
+/// Rewrite of a path, `None` when the path is left unchanged.
+type PathOperation = Rc<dyn Fn(&Path) -> Option<Path>>;
 
 #[derive(Clone)]
 pub struct Path {
+    pub leading_colon: Option<Token![::]>,
     pub(super) segments: Punctuated<PathSegment, Token![::]>
 }
 
@@ -22,10 +28,28 @@ impl Path {
         let ident = create_ident(&segment.into());
         let segment = PathSegment::from(ident);
         Self {
+            leading_colon: None,
             segments: Punctuated::single(segment),
         }
     }
 
+    /// Parse a path written as in a type position, e.g. `std::collections::HashMap<String, Vec<u8>>`,
+    /// `::core::ops::Fn(u8) -> bool`, `Iterator<Item = u8>` or `Cow<'a, str>`.
+    pub fn parse(value: &str) -> Result<Self, Error> {
+        // A trait bound is the only syntax where a bare path accepts parenthesized arguments.
+        match syn::parse_str::<TraitBound>(value) {
+            Ok(TraitBound { paren_token: None, modifier: TraitBoundModifier::None, lifetimes: None, path }) => {
+                Ok(Path::from(path))
+            }
+            Ok(_) => Err(Error {
+                description: format!("Invalid path '{}': unexpected bound modifier", value),
+            }),
+            Err(error) => Err(Error {
+                description: format!("Invalid path '{}': {}", value, error),
+            })
+        }
+    }
+
     pub fn join(&mut self, segment: impl Into<String>) -> &mut Self {
         let ident = create_ident(&segment.into());
         let segment = PathSegment::from(ident);
@@ -33,8 +57,17 @@ impl Path {
         self
     }
 
-    fn internal_with_angle_argument(&mut self, argument: GenericArgument) -> &mut Self {
-        let segment = self.segments.last_mut().unwrap();
+    fn segment_mut(&mut self, segment: usize) -> Result<&mut PathSegment, Error> {
+        let length = self.segments.len();
+        self.segments.iter_mut()
+            .nth(segment)
+            .ok_or(Error {
+                description: format!("Segment {} out of range of a {} segments path", segment, length),
+            })
+    }
+
+    fn angle_arguments_mut(&mut self, segment: usize) -> Result<&mut AngleBracketedGenericArguments, Error> {
+        let segment = self.segment_mut(segment)?;
         if segment.arguments == PathArguments::None {
             let arguments = AngleBracketedGenericArguments {
                 colon2_token: None,
@@ -45,16 +78,15 @@ impl Path {
             segment.arguments = PathArguments::AngleBracketed(arguments);
         }
         match segment.arguments {
-            PathArguments::AngleBracketed(ref mut value) => {
-                value.args.push(argument);
-            }
-            _ => panic!("Unsupported path arguments"),
+            PathArguments::AngleBracketed(ref mut value) => Ok(value),
+            _ => Err(Error {
+                description: format!("Segment '{}' has parenthesized arguments", segment.ident),
+            })
         }
-        self
     }
 
-    fn internal_with_parenthesized_argument(&mut self, argument: Type) -> &mut Self {
-        let segment = self.segments.last_mut().unwrap();
+    fn parenthesized_arguments_mut(&mut self, segment: usize) -> Result<&mut ParenthesizedGenericArguments, Error> {
+        let segment = self.segment_mut(segment)?;
         if segment.arguments == PathArguments::None {
             let arguments = ParenthesizedGenericArguments {
                 paren_token: Default::default(),
@@ -64,10 +96,118 @@ impl Path {
             segment.arguments = PathArguments::Parenthesized(arguments);
         }
         match segment.arguments {
-            PathArguments::Parenthesized(ref mut value) => {
-                value.inputs.push(argument);
+            PathArguments::Parenthesized(ref mut value) => Ok(value),
+            _ => Err(Error {
+                description: format!("Segment '{}' has angle bracketed arguments", segment.ident),
+            })
+        }
+    }
+
+    fn internal_with_angle_argument(&mut self, argument: GenericArgument) -> &mut Self {
+        let segment = self.segments.len() - 1;
+        match self.angle_arguments_mut(segment) {
+            Ok(value) => value.args.push(argument),
+            Err(_) => panic!("Unsupported path arguments"),
+        }
+        self
+    }
+
+    fn internal_with_parenthesized_argument(&mut self, argument: Type) -> &mut Self {
+        let segment = self.segments.len() - 1;
+        match self.parenthesized_arguments_mut(segment) {
+            Ok(value) => value.inputs.push(argument),
+            Err(_) => panic!("Unsupported path arguments"),
+        }
+        self
+    }
+
+    /// Push a lifetime (e.g. `'a`) to the angle bracketed arguments of the segment at `segment`.
+    pub fn push_lifetime(&mut self, segment: usize, lifetime: &str) -> Result<&mut Self, Error> {
+        let lifetime = match lifetime.starts_with('\'') {
+            true => lifetime.to_string(),
+            false => format!("'{}", lifetime),
+        };
+        let lifetime = Lifetime::new(&lifetime, Span::call_site());
+        self.angle_arguments_mut(segment)?.args.push(GenericArgument::Lifetime(lifetime));
+        Ok(self)
+    }
+
+    /// Push a const argument (e.g. `4` or `{ N + 1 }`) to the segment at `segment`.
+    pub fn push_const(&mut self, segment: usize, expr: Expr) -> Result<&mut Self, Error> {
+        self.angle_arguments_mut(segment)?.args.push(GenericArgument::Const(expr));
+        Ok(self)
+    }
+
+    /// Push an associated type binding (e.g. `Item = u8`) to the segment at `segment`.
+    pub fn push_assoc_type(&mut self, segment: usize, ident: &str, type_: Type) -> Result<&mut Self, Error> {
+        let assoc_type = AssocType {
+            ident: create_ident(ident),
+            generics: None,
+            eq_token: Default::default(),
+            ty: type_,
+        };
+        self.angle_arguments_mut(segment)?.args.push(GenericArgument::AssocType(assoc_type));
+        Ok(self)
+    }
+
+    /// Set the return type of the parenthesized arguments of the segment at `segment`, e.g. `R`
+    /// in `Fn(u8) -> R`.
+    pub fn set_return_type(&mut self, segment: usize, type_: Type) -> Result<&mut Self, Error> {
+        self.parenthesized_arguments_mut(segment)?.output = ReturnType::Type(Default::default(), Box::new(type_));
+        Ok(self)
+    }
+
+    /// Arguments of the segment at `segment`. The inputs of parenthesized arguments are returned
+    /// as type arguments.
+    pub fn arguments_of(&self, segment: usize) -> Result<Vec<GenericArgument>, Error> {
+        let segment = self.segments.iter()
+            .nth(segment)
+            .ok_or(Error {
+                description: format!("Segment {} out of range of a {} segments path", segment, self.segments.len()),
+            })?;
+        let arguments = match &segment.arguments {
+            PathArguments::None => Vec::new(),
+            PathArguments::AngleBracketed(value) => value.args.iter().cloned().collect(),
+            PathArguments::Parenthesized(value) => value.inputs.iter()
+                .cloned()
+                .map(GenericArgument::Type)
+                .collect(),
+        };
+        Ok(arguments)
+    }
+
+    /// Replace the argument at `index` of the segment at `segment` and return the previous one.
+    /// Only type arguments are accepted in parenthesized arguments.
+    pub fn replace_argument(
+        &mut self,
+        segment: usize,
+        index: usize,
+        argument: GenericArgument,
+    ) -> Result<GenericArgument, Error> {
+        let segment = self.segment_mut(segment)?;
+        let out_of_range = Error {
+            description: format!("Argument {} out of range of segment '{}'", index, segment.ident),
+        };
+        match (&mut segment.arguments, argument) {
+            (PathArguments::AngleBracketed(value), argument) => {
+                let previous_argument = value.args.iter_mut().nth(index).ok_or(out_of_range)?;
+                Ok(std::mem::replace(previous_argument, argument))
             }
-            _ => panic!("Unsupported path arguments"),
+            (PathArguments::Parenthesized(value), GenericArgument::Type(argument)) => {
+                let previous_argument = value.inputs.iter_mut().nth(index).ok_or(out_of_range)?;
+                Ok(GenericArgument::Type(std::mem::replace(previous_argument, argument)))
+            }
+            (PathArguments::Parenthesized(_), _) => Err(Error {
+                description: format!("Segment '{}' only accepts type arguments", segment.ident),
+            }),
+            (PathArguments::None, _) => Err(out_of_range),
+        }
+    }
+
+    /// Remove the arguments of every segment, e.g. `Vec<u8>::new` becomes `Vec::new`.
+    pub fn strip_arguments(&mut self) -> &mut Self {
+        for segment in self.segments.iter_mut() {
+            segment.arguments = PathArguments::None;
         }
         self
     }
@@ -111,55 +251,172 @@ impl Path {
         let mut segments = Punctuated::new();
         segments.push(segment.clone());
         Path {
+            leading_colon: None,
             segments,
         }
     }
 
+    /// Trim `prefix` from the start of the path and from the paths of its generic arguments.
+    /// Returns whether anything has been trimmed.
     pub fn trim_start(&mut self, prefix: &Path) -> bool {
-        fn trim_path_segments(segments: &mut Punctuated<PathSegment, Token![::]>, count: usize) {
-            let new_path_segments = segments.iter()
-                .skip(count)
-                .map(|segment| segment.clone())
-                .collect::<Punctuated<PathSegment, Token![::]>>();
-            *segments = new_path_segments;
+        match self.strip_prefix(prefix) {
+            Some(value) => {
+                *self = value;
+                true
+            }
+            None => false,
         }
-        fn trim_path_segment_in_generic_arguments(segment: &mut PathSegment, prefix: &Path) -> bool {
-            match segment.arguments {
-                PathArguments::AngleBracketed(ref mut value) => {
-                    let had_been_trimmed = false;
-                    for argument in value.args.iter_mut() {
-                        match argument {
-                            GenericArgument::Type(value) => {
-                                functions::trim_path::from_type(value, prefix);
-                            }
-                            _ => {}
-                        };
+    }
+
+    fn starts_with_idents(&self, prefix: &Path) -> bool {
+        !prefix.segments.is_empty()
+            && (prefix.leading_colon.is_none() || self.leading_colon.is_some())
+            && self.segments.len() >= prefix.segments.len()
+            && self.segments.iter().zip(prefix.segments.iter()).all(|(a, b)| a.ident == b.ident)
+    }
+
+    /// Apply `operation` to the path, then to every path found in its generic arguments,
+    /// recursively. Returns `None` when nothing changed.
+    fn map_recursively(&self, operation: PathOperation) -> Option<Path> {
+        let path = operation(self);
+        let is_changed = path.is_some();
+        let mut syn_path = path.unwrap_or(self.clone()).to_syn_path();
+        let is_nested_changed = Rc::new(Cell::new(false));
+        PathWalker::for_each_argument_type(&mut syn_path, |type_| {
+            let operation = operation.clone();
+            let is_nested_changed = is_nested_changed.clone();
+            let mut context = Context {
+                type_predicate: None,
+                expr_predicate: None,
+                stmt_predicate: None,
+                path_predicate: Some(Box::new(move |value| {
+                    if let Some(path) = Path::from(&*value).map_recursively(operation.clone()) {
+                        *value = path.to_syn_path();
+                        is_nested_changed.set(true);
                     }
-                    had_been_trimmed
-                }
-                _ => false
-            }
+                    true
+                })),
+                ident_predicate: None,
+            };
+            TypeWalker::walk(type_, &mut context);
+        });
+        match is_changed || is_nested_changed.get() {
+            true => Some(Path::from(syn_path)),
+            false => None,
         }
-        let mut path_segments = prefix.segments.iter()
-            .map(|segment| segment.ident.to_string())
-            .collect::<VecDeque<_>>();
-        let mut trim_segment_count = 1;
-        for segment in self.iter_mut() {
-            trim_path_segment_in_generic_arguments(segment, prefix);
-            if path_segments.is_empty() {
-                continue;
+    }
+
+    /// Path without `prefix` (compared by idents), e.g. `Rc<Weak<u8>>` for
+    /// `std::rc::Rc<std::rc::Weak<u8>>` without `std::rc`. The prefix is also stripped from the
+    /// paths of generic arguments and the last segment is always kept. Returns `None` when nothing
+    /// has been stripped.
+    pub fn strip_prefix(&self, prefix: &Path) -> Option<Path> {
+        let prefix = prefix.clone();
+        self.map_recursively(Rc::new(move |path: &Path| {
+            if !path.starts_with_idents(&prefix) || path.segments.len() == prefix.segments.len() {
+                return None;
+            }
+            Some(Path::from(path.segments.iter().skip(prefix.segments.len()).collect::<Vec<_>>()))
+        }))
+    }
+
+    /// Path without `suffix` (compared by idents), e.g. `crate::models` for
+    /// `crate::models::Model::new` without `Model::new`. The suffix is also stripped from the paths
+    /// of generic arguments and the first segment is always kept. Returns `None` when nothing has
+    /// been stripped.
+    pub fn strip_suffix(&self, suffix: &Path) -> Option<Path> {
+        let suffix = suffix.clone();
+        self.map_recursively(Rc::new(move |path: &Path| {
+            let length = path.segments.len();
+            let suffix_length = suffix.segments.len();
+            if suffix_length == 0 || length <= suffix_length {
+                return None;
             }
-            let segment_to_compare = path_segments.pop_front().unwrap();
-            let segment_ident = segment.ident.to_string();
-            if segment_ident == segment_to_compare && path_segments.is_empty() == false {
-                trim_segment_count += 1;
+            let is_matching = path.segments.iter()
+                .skip(length - suffix_length)
+                .zip(suffix.segments.iter())
+                .all(|(a, b)| a.ident == b.ident);
+            if !is_matching {
+                return None;
             }
-            else if segment_ident == segment_to_compare && path_segments.is_empty() {
-                trim_path_segments(&mut self.segments, trim_segment_count);
-                return true;
+            let mut stripped_path = Path::from(path.segments.iter().take(length - suffix_length).collect::<Vec<_>>());
+            stripped_path.leading_colon = path.leading_colon;
+            Some(stripped_path)
+        }))
+    }
+
+    /// Path with the `old` prefix (compared by idents) replaced by `new`, e.g.
+    /// `crate::entities::Model` for `crate::models::Model`, `crate::models` and `crate::entities`.
+    /// Paths of generic arguments are also rewritten. Returns `None` when nothing has been
+    /// replaced.
+    pub fn replace_prefix(&self, old: &Path, new: &Path) -> Option<Path> {
+        let old = old.clone();
+        let new = new.clone();
+        self.map_recursively(Rc::new(move |path: &Path| {
+            if !path.starts_with_idents(&old) {
+                return None;
+            }
+            let mut replaced_path = new.clone();
+            replaced_path.segments.extend(path.segments.iter().skip(old.segments.len()).cloned());
+            Some(replaced_path)
+        }))
+    }
+
+    fn is_crate_path(&self) -> bool {
+        self.leading_colon.is_none()
+            && self.segments.first().map(|segment| segment.ident == "crate").unwrap_or(false)
+    }
+
+    /// Path relative to the module `from_module`, both being absolute (`crate::...`), e.g.
+    /// `crate::a::x::Y` relative to `crate::a::b::c` is `super::super::x::Y` and relative to
+    /// `crate::a::x` is `self::Y`. Other paths are returned unchanged.
+    pub fn relative_to(&self, from_module: &Path) -> Path {
+        if !self.is_crate_path() || !from_module.is_crate_path() {
+            return self.clone();
+        }
+        let common_length = self.segments.iter()
+            .zip(from_module.segments.iter())
+            .take_while(|(a, b)| a.ident == b.ident)
+            .count();
+        let mut segments = match from_module.segments.len() - common_length {
+            0 => vec![PathSegment::from(create_ident("self"))],
+            count => vec![PathSegment::from(create_ident("super")); count],
+        };
+        segments.extend(self.segments.iter().skip(common_length).cloned());
+        Path::from(segments)
+    }
+
+    /// Absolute form of a path written in `current_module`, e.g. `super::x::Y` written in
+    /// `crate::a::b` is `crate::a::x::Y`. Paths not starting with `crate`, `self` or `super`
+    /// are returned unchanged.
+    pub fn resolve(current_module: &Path, relative: &Path) -> Result<Path, Error> {
+        let first_segment = match relative.segments.first() {
+            Some(value) if relative.leading_colon.is_none() => value.ident.to_string(),
+            _ => return Ok(relative.clone()),
+        };
+        if !matches!(first_segment.as_str(), "crate" | "self" | "super") {
+            return Ok(relative.clone());
+        }
+        if !current_module.is_crate_path() {
+            return Err(Error {
+                description: format!("Module '{}' is not an absolute path", current_module),
+            });
+        }
+        let mut segments = current_module.segments.iter().cloned().collect::<Vec<_>>();
+        for (index, segment) in relative.segments.iter().enumerate() {
+            match segment.ident.to_string().as_str() {
+                "crate" if index == 0 => segments.truncate(1),
+                "self" if index == 0 => {}
+                "super" if segments.len() > 1 => {
+                    segments.pop();
+                }
+                "super" => return Err(Error {
+                    description: format!("Path '{}' goes beyond the crate root from '{}'", relative, current_module),
+                }),
+                _ => segments.push(segment.clone()),
             }
         }
-        false
+        Ok(Path::from(segments))
     }
 
     pub fn decompose_arguments(&self, ) -> Result<Vec<Path>, Error> {
@@ -192,6 +449,14 @@ impl Path {
         }
     }
 
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
     pub fn last(&self) -> Option<&PathSegment> {
         self.segments.last()
     }
@@ -206,7 +471,7 @@ impl Path {
 
     pub fn to_syn_path(&self) -> syn::Path {
         syn::Path {
-            leading_colon: None,
+            leading_colon: self.leading_colon,
             segments: self.segments.clone(),
         }
     }
@@ -214,7 +479,7 @@ impl Path {
 
 impl PartialEq<Self> for Path {
     fn eq(&self, other: &Self) -> bool {
-        self.segments == other.segments
+        self.leading_colon.is_some() == other.leading_colon.is_some() && self.segments == other.segments
     }
 }
 
@@ -224,6 +489,7 @@ impl Eq for Path {}
 impl Default for Path {
     fn default() -> Self {
         Self {
+            leading_colon: None,
             segments: Default::default(),
         }
     }
@@ -231,20 +497,60 @@ impl Default for Path {
 
 impl Display for Path {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let separator = PathSep::default().to_token_stream().to_string();
-        let segments = self.segments.iter()
-            .map(|segment| segment.ident.to_string())
-            .join(separator.as_str());
-        write!(f, "{}", segments)?;
-        match self.decompose_arguments() {
-            Ok(value) => {
-                let arguments = value.iter()
-                    .join(Comma::default().to_token_stream().to_string().as_str());
-                write!(f, "<{}>", arguments)
+        write!(f, "{}", format_tokens(self.to_syn_path().to_token_stream()))
+    }
+}
+
+/// Format tokens the way a path is written by hand, e.g. `HashMap<String, Vec<u8>>` or
+/// `Fn(&'a str) -> bool`, so that the result can be parsed back with [`Path::parse`].
+fn format_tokens(tokens: TokenStream) -> String {
+    let mut output = String::new();
+    let mut is_word = false;
+    for token in tokens {
+        match token {
+            TokenTree::Ident(value) => {
+                if is_word {
+                    output.push(' ');
+                }
+                output.push_str(&value.to_string());
+                is_word = true;
+            }
+            TokenTree::Literal(value) => {
+                if is_word {
+                    output.push(' ');
+                }
+                output.push_str(&value.to_string());
+                is_word = true;
+            }
+            TokenTree::Punct(value) => {
+                let is_joint = value.spacing() == Spacing::Joint;
+                match value.as_char() {
+                    ',' | ';' => output.push_str(&format!("{} ", value.as_char())),
+                    '=' | '+' if !is_joint => output.push_str(&format!(" {} ", value.as_char())),
+                    '-' if is_joint => output.push_str(" -"),
+                    '>' if output.ends_with(" -") => output.push_str("> "),
+                    char => output.push(char),
+                }
+                is_word = false;
+            }
+            TokenTree::Group(value) => {
+                let (open, close) = match value.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Brace => ("{ ", " }"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::None => ("", ""),
+                };
+                if is_word && value.delimiter() == Delimiter::Bracket {
+                    output.push(' ');
+                }
+                output.push_str(open);
+                output.push_str(&format_tokens(value.stream()));
+                output.push_str(close);
+                is_word = true;
             }
-            Err(_) => Ok(())
         }
     }
+    output
 }
 
 impl Debug for Path {
@@ -270,7 +576,10 @@ impl From<&std::path::Path> for Path {
 
 impl From<syn::Path> for Path {
     fn from(value: syn::Path) -> Self {
-        let mut path = Path::default();
+        let mut path = Path {
+            leading_colon: value.leading_colon,
+            ..Default::default()
+        };
         for segment in value.segments {
             path.segments.push(segment);
         }
@@ -280,7 +589,10 @@ impl From<syn::Path> for Path {
 
 impl From<&syn::Path> for Path {
     fn from(value: &syn::Path) -> Self {
-        let mut path = Path::default();
+        let mut path = Path {
+            leading_colon: value.leading_colon,
+            ..Default::default()
+        };
         for segment in &value.segments {
             path.segments.push(segment.clone());
         }
@@ -0,0 +1,266 @@
+use crate::error::Error;
+use crate::utils::path::Path;
+use std::fmt::{Display, Formatter};
+use syn::{GenericArgument, PathArguments, PathSegment, Type};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum ArgumentPattern {
+    /// `_`, any argument.
+    Any,
+    Path(PathPattern),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum SegmentPattern {
+    /// Segment with this ident, any ident for `*`. Its arguments are only checked when the
+    /// pattern has some.
+    One {
+        ident: Option<String>,
+        arguments: Option<Vec<ArgumentPattern>>,
+    },
+    /// `**`, any number of segments, none included.
+    AnyMany,
+}
+
+/// Part of a path matched by a wildcard of a [`PathPattern`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Capture {
+    /// Segments matched by `*` or `**`.
+    Segments(Path),
+    /// Generic argument matched by `_`.
+    Argument(Box<GenericArgument>),
+}
+
+/// Pattern matching paths, e.g. `std::*::HashMap`, `**::Option<_>` or `crate::models::*`.
+/// `*` matches one segment, `**` any number of segments and `_` one generic argument. Wildcards
+/// are captured in the order they appear in the pattern.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PathPattern {
+    leading_colon: bool,
+    segments: Vec<SegmentPattern>,
+}
+
+impl PathPattern {
+    /// Split `value` on `separator` outside of angle brackets.
+    fn split(value: &str, separator: &str) -> Result<Vec<String>, Error> {
+        let mut parts = vec![String::new()];
+        let mut depth = 0;
+        let mut rest = value;
+        while let Some(char) = rest.chars().next() {
+            if depth == 0 && rest.starts_with(separator) {
+                parts.push(String::new());
+                rest = &rest[separator.len()..];
+                continue;
+            }
+            match char {
+                '<' => depth += 1,
+                '>' if depth == 0 => return Err(Error {
+                    description: format!("Unbalanced '>' in pattern '{}'", value),
+                }),
+                '>' => depth -= 1,
+                _ => {}
+            }
+            parts.last_mut().unwrap().push(char);
+            rest = &rest[char.len_utf8()..];
+        }
+        match depth {
+            0 => Ok(parts.into_iter().map(|part| part.trim().to_string()).collect()),
+            _ => Err(Error {
+                description: format!("Unbalanced '<' in pattern '{}'", value),
+            })
+        }
+    }
+
+    fn parse_segment(value: &str) -> Result<SegmentPattern, Error> {
+        let (ident, arguments) = match value.find('<') {
+            Some(index) if value.ends_with('>') => (value[..index].trim(), Some(&value[index + 1..value.len() - 1])),
+            Some(_) => return Err(Error {
+                description: format!("Invalid segment pattern '{}'", value),
+            }),
+            None => (value, None),
+        };
+        let is_ident = !ident.is_empty() && ident.chars().all(|char| char.is_alphanumeric() || char == '_');
+        let ident = match (ident, arguments) {
+            ("*", _) => None,
+            ("**", None) => return Ok(SegmentPattern::AnyMany),
+            _ if is_ident => Some(ident.to_string()),
+            _ => return Err(Error {
+                description: format!("Invalid segment pattern '{}'", value),
+            }),
+        };
+        let arguments = match arguments {
+            Some(arguments) => {
+                let arguments = Self::split(arguments, ",")?.into_iter()
+                    .map(|argument| match argument.as_str() {
+                        "_" => Ok(ArgumentPattern::Any),
+                        _ => Ok(ArgumentPattern::Path(PathPattern::parse(&argument)?)),
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+                Some(arguments)
+            }
+            None => None,
+        };
+        Ok(SegmentPattern::One {
+            ident,
+            arguments,
+        })
+    }
+
+    pub fn parse(value: &str) -> Result<Self, Error> {
+        let value = value.trim();
+        let (leading_colon, value) = match value.strip_prefix("::") {
+            Some(value) => (true, value),
+            None => (false, value),
+        };
+        let segments = Self::split(value, "::")?.iter()
+            .map(|segment| Self::parse_segment(segment))
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(Self {
+            leading_colon,
+            segments,
+        })
+    }
+
+    fn match_arguments(
+        patterns: &[ArgumentPattern],
+        segment: &PathSegment,
+        captures: &mut Vec<Capture>,
+    ) -> bool {
+        let arguments = match &segment.arguments {
+            PathArguments::AngleBracketed(value) => value.args.iter().collect::<Vec<_>>(),
+            _ => Vec::new(),
+        };
+        if arguments.len() != patterns.len() {
+            return false;
+        }
+        patterns.iter().zip(arguments).all(|(pattern, argument)| match (pattern, argument) {
+            (ArgumentPattern::Any, argument) => {
+                captures.push(Capture::Argument(Box::new(argument.clone())));
+                true
+            }
+            (ArgumentPattern::Path(pattern), GenericArgument::Type(Type::Path(value))) if value.qself.is_none() => {
+                pattern.match_path(&value.path.segments.iter().cloned().collect::<Vec<_>>(), captures)
+            }
+            _ => false,
+        })
+    }
+
+    fn match_segments(patterns: &[SegmentPattern], segments: &[PathSegment], captures: &mut Vec<Capture>) -> bool {
+        let (pattern, patterns) = match patterns.split_first() {
+            Some(value) => value,
+            None => return segments.is_empty(),
+        };
+        let captures_length = captures.len();
+        let is_matching = match pattern {
+            SegmentPattern::AnyMany => {
+                for count in 0..=segments.len() {
+                    captures.push(Capture::Segments(Path::from(segments[..count].to_vec())));
+                    if Self::match_segments(patterns, &segments[count..], captures) {
+                        return true;
+                    }
+                    captures.truncate(captures_length);
+                }
+                false
+            }
+            SegmentPattern::One { ident, arguments } => match segments.split_first() {
+                Some((segment, segments)) => {
+                    let is_ident_matching = match ident {
+                        Some(ident) => segment.ident == ident,
+                        None => {
+                            captures.push(Capture::Segments(Path::from(segment)));
+                            true
+                        }
+                    };
+                    is_ident_matching
+                        && arguments.as_ref()
+                            .map(|arguments| Self::match_arguments(arguments, segment, captures))
+                            .unwrap_or(true)
+                        && Self::match_segments(patterns, segments, captures)
+                }
+                None => false,
+            },
+        };
+        if !is_matching {
+            captures.truncate(captures_length);
+        }
+        is_matching
+    }
+
+    fn match_path(&self, segments: &[PathSegment], captures: &mut Vec<Capture>) -> bool {
+        Self::match_segments(&self.segments, segments, captures)
+    }
+
+    fn is_leading_colon_matching(&self, path: &Path) -> bool {
+        !self.leading_colon || path.leading_colon.is_some()
+    }
+
+    pub fn matches(&self, path: &Path) -> bool {
+        self.captures(path).is_some()
+    }
+
+    /// Captures of the wildcards when the pattern matches the whole path.
+    pub fn captures(&self, path: &Path) -> Option<Vec<Capture>> {
+        if !self.is_leading_colon_matching(path) {
+            return None;
+        }
+        let segments = path.iter().cloned().collect::<Vec<_>>();
+        let mut captures = Vec::new();
+        match self.match_path(&segments, &mut captures) {
+            true => Some(captures),
+            false => None,
+        }
+    }
+
+    /// Length of the longest prefix of `path` matched by the pattern, the last segment excluded,
+    /// e.g. 2 for `std::*` and `std::collections::HashMap`.
+    pub fn match_prefix(&self, path: &Path) -> Option<usize> {
+        if !self.is_leading_colon_matching(path) {
+            return None;
+        }
+        let segments = path.iter().cloned().collect::<Vec<_>>();
+        (1..segments.len())
+            .rev()
+            .find(|count| self.match_path(&segments[..*count], &mut Vec::new()))
+    }
+}
+
+impl Display for ArgumentPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArgumentPattern::Any => write!(f, "_"),
+            ArgumentPattern::Path(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl Display for SegmentPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SegmentPattern::One { ident, arguments } => {
+                write!(f, "{}", ident.as_deref().unwrap_or("*"))?;
+                match arguments {
+                    Some(arguments) => {
+                        let arguments = arguments.iter()
+                            .map(|argument| argument.to_string())
+                            .collect::<Vec<_>>();
+                        write!(f, "<{}>", arguments.join(", "))
+                    }
+                    None => Ok(()),
+                }
+            }
+            SegmentPattern::AnyMany => write!(f, "**"),
+        }
+    }
+}
+
+impl Display for PathPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.leading_colon {
+            write!(f, "::")?;
+        }
+        let segments = self.segments.iter()
+            .map(|segment| segment.to_string())
+            .collect::<Vec<_>>();
+        write!(f, "{}", segments.join("::"))
+    }
+}
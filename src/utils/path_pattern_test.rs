@@ -0,0 +1,74 @@
+use crate::utils::path::Path;
+use crate::utils::path_pattern::{Capture, PathPattern};
+use pretty_assertions::assert_eq;
+use quote::ToTokens;
+use rstest::rstest;
+
+#[rstest]
+#[case("std::*::HashMap", "std::collections::HashMap", true)]
+#[case("std::*::HashMap", "std::HashMap", false)]
+#[case("std::*::HashMap", "std::collections::hash_map::HashMap", false)]
+#[case("std::**::HashMap", "std::collections::hash_map::HashMap", true)]
+#[case("std::**::HashMap", "std::HashMap", true)]
+#[case("**::Option<_>", "Option<u8>", true)]
+#[case("**::Option<_>", "std::option::Option<Vec<u8>>", true)]
+#[case("**::Option<_>", "Option", false)]
+#[case("**::Option<_>", "Result<u8, ()>", false)]
+#[case("**::Option<Vec<_>>", "Option<Vec<u8>>", true)]
+#[case("**::Option<Vec<_>>", "Option<Box<u8>>", false)]
+#[case("Option", "Option<u8>", true)]
+#[case("crate::models::*", "crate::models::Model", true)]
+#[case("crate::models::*", "crate::models", false)]
+#[case("crate::models::*", "crate::services::Model", false)]
+#[case("::std::**", "::std::rc::Rc", true)]
+#[case("::std::**", "std::rc::Rc", false)]
+fn matches(#[case] pattern: &str, #[case] path: &str, #[case] expected: bool) {
+    let pattern = PathPattern::parse(pattern).unwrap();
+    let path = Path::parse(path).unwrap();
+    assert_eq!(expected, pattern.matches(&path));
+}
+
+#[rstest]
+fn captures() {
+    let pattern = PathPattern::parse("std::**::*<_, Vec<_>>").unwrap();
+    let path = Path::parse("std::collections::hash_map::HashMap<String, Vec<u8>>").unwrap();
+    let captures = pattern.captures(&path).unwrap()
+        .iter()
+        .map(|capture| match capture {
+            Capture::Segments(value) => value.to_string(),
+            Capture::Argument(value) => value.to_token_stream().to_string(),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["collections::hash_map", "HashMap<String, Vec<u8>>", "String", "u8"], captures);
+}
+
+#[rstest]
+#[case("std::*", "std::collections::HashMap", Some(2))]
+#[case("std::**", "std::collections::HashMap", Some(2))]
+#[case("std::collections::HashMap", "std::collections::HashMap", None)]
+#[case("core::**", "std::collections::HashMap", None)]
+fn match_prefix(#[case] pattern: &str, #[case] path: &str, #[case] expected: Option<usize>) {
+    let pattern = PathPattern::parse(pattern).unwrap();
+    let path = Path::parse(path).unwrap();
+    assert_eq!(expected, pattern.match_prefix(&path));
+}
+
+#[rstest]
+#[case("std::*::HashMap")]
+#[case("::std::**")]
+#[case("**::Option<_>")]
+#[case("**::HashMap<String, Vec<_>>")]
+fn display(#[case] pattern: &str) {
+    assert_eq!(pattern, PathPattern::parse(pattern).unwrap().to_string());
+}
+
+#[rstest]
+#[case("")]
+#[case("std::")]
+#[case("std::Option<_")]
+#[case("std::Option>")]
+#[case("std::Opt ion")]
+#[case("Option<>::x")]
+fn parse_invalid(#[case] pattern: &str) {
+    assert!(PathPattern::parse(pattern).is_err());
+}
@@ -0,0 +1,225 @@
+use crate::utils::path::Path;
+use pretty_assertions::assert_eq;
+use quote::{quote, ToTokens};
+use rstest::rstest;
+
+#[rstest]
+#[case("HashMap", quote!(HashMap))]
+#[case("std::collections::HashMap<String, Vec<u8>>", quote!(std::collections::HashMap<String, Vec<u8> >))]
+#[case("Cow<'a, str>", quote!(Cow<'a, str>))]
+#[case("Iterator<Item = u8>", quote!(Iterator<Item = u8>))]
+#[case("Fn(u8, &str) -> bool", quote!(Fn(u8, &str) -> bool))]
+#[case("crate::models::Model::new", quote!(crate::models::Model::new))]
+fn parse(#[case] value: &str, #[case] expected_quote: proc_macro2::TokenStream) {
+    let path = Path::parse(value).unwrap();
+    assert_eq!(
+        expected_quote.to_string(),
+        path.to_syn_path().to_token_stream().to_string()
+    );
+}
+
+#[rstest]
+fn parse_leading_colon() {
+    let path = Path::parse("::std::rc::Rc<T>").unwrap();
+    assert!(path.leading_colon.is_some());
+    assert_eq!(
+        quote!(::std::rc::Rc<T>).to_string(),
+        path.to_syn_path().to_token_stream().to_string()
+    );
+}
+
+#[rstest]
+#[case("")]
+#[case("std::")]
+#[case("Vec<u8")]
+#[case("std::collections::HashMap<String, Vec<u8>> extra")]
+#[case("&str")]
+#[case("<T as Trait>::Output")]
+#[case("?Sized")]
+fn parse_invalid(#[case] value: &str) {
+    assert!(Path::parse(value).is_err());
+}
+
+#[rstest]
+#[case("HashMap")]
+#[case("::std::collections::HashMap<String, Vec<u8>>")]
+#[case("a<T>::b<U>")]
+#[case("Vec::<u8>::new")]
+#[case("Cow<'a, str>")]
+#[case("Iterator<Item = Option<&'static str>>")]
+#[case("Fn(u8, &mut [u8; 4]) -> Box<dyn Error + Send>")]
+#[case("FnOnce()")]
+#[case("Array<u8, 4>")]
+#[case("Array<u8, { N + 1 }>")]
+#[case("Callback<*const u8, (u8, bool), impl Iterator<Item = u8>>")]
+fn display(#[case] value: &str) {
+    let path = Path::parse(value).unwrap();
+    assert_eq!(value, path.to_string());
+    assert_eq!(path, Path::parse(&path.to_string()).unwrap());
+}
+
+#[rstest]
+fn from_syn_path() {
+    let syn_path: syn::Path = syn::parse_quote!(::core::option::Option<u8>);
+    let path = Path::from(&syn_path);
+    assert_eq!("::core::option::Option<u8>", path.to_string());
+    assert_eq!(syn_path, path.to_syn_path());
+    assert_ne!(path, Path::parse("core::option::Option<u8>").unwrap());
+}
+
+#[rstest]
+fn push_arguments() {
+    let mut path = Path::parse("std::borrow::Cow").unwrap();
+    path.push_lifetime(2, "a").unwrap()
+        .push_lifetime(2, "'b").unwrap();
+    assert_eq!("std::borrow::Cow<'a, 'b>", path.to_string());
+    let mut path = Path::parse("Array").unwrap();
+    path.push_const(0, syn::parse_quote!(4)).unwrap()
+        .push_const(0, syn::parse_quote!({ N + 1 })).unwrap();
+    assert_eq!("Array<4, { N + 1 }>", path.to_string());
+    let mut path = Path::parse("Iterator::next").unwrap();
+    path.push_assoc_type(0, "Item", syn::parse_quote!(Option<u8>)).unwrap();
+    assert_eq!("Iterator<Item = Option<u8>>::next", path.to_string());
+    let mut path = Path::parse("Fn(u8)").unwrap();
+    path.set_return_type(0, syn::parse_quote!(bool)).unwrap();
+    assert_eq!("Fn(u8) -> bool", path.to_string());
+}
+
+#[rstest]
+fn push_arguments_invalid() {
+    let mut path = Path::parse("Fn(u8)").unwrap();
+    assert!(path.push_lifetime(0, "'a").is_err());
+    assert!(path.push_lifetime(1, "'a").is_err());
+    let mut path = Path::parse("Vec<u8>").unwrap();
+    assert!(path.set_return_type(0, syn::parse_quote!(bool)).is_err());
+}
+
+#[rstest]
+fn arguments_of() {
+    let path = Path::parse("a<'a, T, 4, Item = u8>::b::c(u8, bool)").unwrap();
+    let arguments = path.arguments_of(0).unwrap();
+    assert_eq!(
+        vec!["'a", "T", "4", "Item = u8"],
+        arguments.iter().map(|argument| argument.to_token_stream().to_string()).collect::<Vec<_>>()
+    );
+    assert!(path.arguments_of(1).unwrap().is_empty());
+    assert_eq!(2, path.arguments_of(2).unwrap().len());
+    assert!(path.arguments_of(3).is_err());
+}
+
+#[rstest]
+fn replace_argument() {
+    let mut path = Path::parse("HashMap<String, u8>::new").unwrap();
+    let previous_argument = path.replace_argument(0, 1, syn::parse_quote!(Vec<u8>)).unwrap();
+    assert_eq!("u8", previous_argument.to_token_stream().to_string());
+    assert_eq!("HashMap<String, Vec<u8>>::new", path.to_string());
+    assert!(path.replace_argument(0, 2, syn::parse_quote!(u8)).is_err());
+    assert!(path.replace_argument(1, 0, syn::parse_quote!(u8)).is_err());
+    let mut path = Path::parse("Fn(u8)").unwrap();
+    path.replace_argument(0, 0, syn::parse_quote!(&str)).unwrap();
+    assert_eq!("Fn(&str)", path.to_string());
+    assert!(path.replace_argument(0, 0, syn::parse_quote!('a)).is_err());
+}
+
+#[rstest]
+fn strip_arguments() {
+    let mut path = Path::parse("::a<T>::b::c(u8) -> bool").unwrap();
+    path.strip_arguments();
+    assert_eq!("::a::b::c", path.to_string());
+}
+
+#[rstest]
+#[case("crate::a::x::Y", "crate::a::b::c", "super::super::x::Y")]
+#[case("crate::a::x::Y", "crate::a::x", "self::Y")]
+#[case("crate::a::Y", "crate::a::b", "super::Y")]
+#[case("crate::Y<crate::a::Z>", "crate::a", "super::Y<crate::a::Z>")]
+#[case("crate::a::Y", "crate", "self::a::Y")]
+#[case("crate::a", "crate::a::b", "super")]
+#[case("std::rc::Rc", "crate::a", "std::rc::Rc")]
+#[case("::std::rc::Rc", "crate::a", "::std::rc::Rc")]
+fn relative_to(#[case] path: &str, #[case] from_module: &str, #[case] expected: &str) {
+    let path = Path::parse(path).unwrap();
+    let from_module = Path::parse(from_module).unwrap();
+    assert_eq!(expected, path.relative_to(&from_module).to_string());
+}
+
+#[rstest]
+#[case("crate::a::b", "super::x::Y", "crate::a::x::Y")]
+#[case("crate::a::b", "super::super::Y", "crate::Y")]
+#[case("crate::a::b", "self::Y::new", "crate::a::b::Y::new")]
+#[case("crate::a::b", "crate::c::Y", "crate::c::Y")]
+#[case("crate::a::b", "std::rc::Rc", "std::rc::Rc")]
+#[case("crate::a::b", "::std::rc::Rc", "::std::rc::Rc")]
+fn resolve(#[case] current_module: &str, #[case] relative: &str, #[case] expected: &str) {
+    let current_module = Path::parse(current_module).unwrap();
+    let relative = Path::parse(relative).unwrap();
+    assert_eq!(expected, Path::resolve(&current_module, &relative).unwrap().to_string());
+}
+
+#[rstest]
+#[case("crate::a", "super::super::Y")]
+#[case("a::b", "super::Y")]
+fn resolve_invalid(#[case] current_module: &str, #[case] relative: &str) {
+    let current_module = Path::parse(current_module).unwrap();
+    let relative = Path::parse(relative).unwrap();
+    assert!(Path::resolve(&current_module, &relative).is_err());
+}
+
+#[rstest]
+fn relative_to_and_resolve() {
+    let path = Path::parse("crate::a::x::Y").unwrap();
+    let module = Path::parse("crate::a::b::c").unwrap();
+    assert_eq!(path, Path::resolve(&module, &path.relative_to(&module)).unwrap());
+}
+
+#[rstest]
+#[case("a::b::c", "a::b", Some("c"))]
+#[case("a::x::b", "a::b", None)]
+#[case("a::b", "a::b", None)]
+#[case("x::a::b", "a::b", None)]
+#[case("::a::b::c", "a::b", Some("c"))]
+#[case("a::b::c", "::a::b", None)]
+#[case("std::rc::Rc<std::rc::Weak<u8>>", "std::rc", Some("Rc<Weak<u8>>"))]
+#[case("Vec<std::rc::Rc<u8>>", "std::rc", Some("Vec<Rc<u8>>"))]
+#[case("Fn(&std::rc::Rc<u8>) -> (std::rc::Rc<u8>, u8)", "std::rc", Some("Fn(&Rc<u8>) -> (Rc<u8>, u8)"))]
+#[case("Vec<u8>", "std::rc", None)]
+fn strip_prefix(#[case] path: &str, #[case] prefix: &str, #[case] expected: Option<&str>) {
+    let path = Path::parse(path).unwrap();
+    let prefix = Path::parse(prefix).unwrap();
+    assert_eq!(expected.map(str::to_string), path.strip_prefix(&prefix).map(|path| path.to_string()));
+}
+
+#[rstest]
+fn trim_start() {
+    let mut path = Path::parse("a::x::b").unwrap();
+    assert!(!path.trim_start(&Path::parse("a::b").unwrap()));
+    assert_eq!("a::x::b", path.to_string());
+    let mut path = Path::parse("Vec<a::b::C>").unwrap();
+    assert!(path.trim_start(&Path::parse("a::b").unwrap()));
+    assert_eq!("Vec<C>", path.to_string());
+}
+
+#[rstest]
+#[case("crate::models::Model::new", "Model::new", Some("crate::models"))]
+#[case("::std::rc::Rc", "Rc", Some("::std::rc"))]
+#[case("Model::new", "Model::new", None)]
+#[case("crate::Model::build", "Model::new", None)]
+#[case("Vec<crate::models::Model>", "Model", Some("Vec<crate::models>"))]
+fn strip_suffix(#[case] path: &str, #[case] suffix: &str, #[case] expected: Option<&str>) {
+    let path = Path::parse(path).unwrap();
+    let suffix = Path::parse(suffix).unwrap();
+    assert_eq!(expected.map(str::to_string), path.strip_suffix(&suffix).map(|path| path.to_string()));
+}
+
+#[rstest]
+#[case("crate::models::Model", "crate::models", "crate::entities", Some("crate::entities::Model"))]
+#[case("crate::models", "crate::models", "crate::entities", Some("crate::entities"))]
+#[case("Vec<crate::models::Model>", "crate::models", "super::entities", Some("Vec<super::entities::Model>"))]
+#[case("crate::models::Model", "std", "core", None)]
+#[case("std::rc::Rc", "std", "::core", Some("::core::rc::Rc"))]
+fn replace_prefix(#[case] path: &str, #[case] old: &str, #[case] new: &str, #[case] expected: Option<&str>) {
+    let path = Path::parse(path).unwrap();
+    let old = Path::parse(old).unwrap();
+    let new = Path::parse(new).unwrap();
+    assert_eq!(expected.map(str::to_string), path.replace_prefix(&old, &new).map(|path| path.to_string()));
+}
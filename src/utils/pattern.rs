@@ -0,0 +1,137 @@
+use crate::utils::create_ident;
+use crate::utils::path::Path;
+use syn::punctuated::Punctuated;
+use syn::{ExprLit, FieldPat, Lit, Member, Pat, PatIdent, PatOr, PatPath, PatRest, PatStruct, PatTuple, PatTupleStruct, PatWild, Token};
+
+/// Builder of `syn::Pat` trees, e.g. for the arms of [`crate::utils::expr::ExprBuilder::match_`].
+pub struct PatternBuilder;
+
+impl PatternBuilder {
+    /// `_`
+    pub fn wildcard() -> Pat {
+        Pat::Wild(PatWild {
+            attrs: vec![],
+            underscore_token: Default::default(),
+        })
+    }
+
+    fn internal_ident(name: &str, is_ref: bool, is_mutable: bool, pattern: Option<Pat>) -> Pat {
+        Pat::Ident(PatIdent {
+            attrs: vec![],
+            by_ref: match is_ref {
+                true => Some(Default::default()),
+                false => None,
+            },
+            mutability: match is_mutable {
+                true => Some(Default::default()),
+                false => None,
+            },
+            ident: create_ident(name),
+            subpat: pattern.map(|pattern| (Default::default(), Box::new(pattern))),
+        })
+    }
+
+    /// `name`
+    pub fn ident(name: &str) -> Pat {
+        Self::internal_ident(name, false, false, None)
+    }
+
+    /// `ref mut name`, `ref name` or `mut name`.
+    pub fn ident_with_modifiers(name: &str, is_ref: bool, is_mutable: bool) -> Pat {
+        Self::internal_ident(name, is_ref, is_mutable, None)
+    }
+
+    /// `name @ pattern`
+    pub fn binding(name: &str, pattern: Pat) -> Pat {
+        Self::internal_ident(name, false, false, Some(pattern))
+    }
+
+    /// Path of a unit variant or a constant, e.g. `None` or `Shape::Empty`.
+    pub fn path(path: Path) -> Pat {
+        Pat::Path(PatPath {
+            attrs: vec![],
+            qself: None,
+            path: path.to_syn_path(),
+        })
+    }
+
+    pub fn lit(lit: Lit) -> Pat {
+        Pat::Lit(ExprLit {
+            attrs: vec![],
+            lit,
+        })
+    }
+
+    /// `path(elements)`, e.g. `Some(value)` or `Shape::Circle(radius, _)`.
+    pub fn tuple_struct(path: Path, elements: Vec<Pat>) -> Pat {
+        Pat::TupleStruct(PatTupleStruct {
+            attrs: vec![],
+            qself: None,
+            path: path.to_syn_path(),
+            paren_token: Default::default(),
+            elems: elements.into_iter().collect(),
+        })
+    }
+
+    /// `(elements)`
+    pub fn tuple(elements: Vec<Pat>) -> Pat {
+        let mut elements = elements.into_iter().collect::<Punctuated<Pat, Token![,]>>();
+        if elements.len() == 1 {
+            elements.push_punct(Default::default());
+        }
+        Pat::Tuple(PatTuple {
+            attrs: vec![],
+            paren_token: Default::default(),
+            elems: elements,
+        })
+    }
+
+    /// `path { fields, .. }`, e.g. `Point { x, y: 0, .. }`. A field whose pattern is the ident
+    /// of the field is written in its shorthand form.
+    pub fn struct_(path: Path, fields: Vec<(&str, Pat)>, has_rest: bool) -> Pat {
+        let mut fields = fields.into_iter()
+            .map(|(name, pattern)| {
+                let is_shorthand = matches!(
+                    &pattern,
+                    Pat::Ident(value) if value.ident == name && value.by_ref.is_none()
+                        && value.mutability.is_none() && value.subpat.is_none()
+                );
+                FieldPat {
+                    attrs: vec![],
+                    member: Member::Named(create_ident(name)),
+                    colon_token: match is_shorthand {
+                        true => None,
+                        false => Some(Default::default()),
+                    },
+                    pat: Box::new(pattern),
+                }
+            })
+            .collect::<Punctuated<FieldPat, Token![,]>>();
+        if has_rest && !fields.is_empty() {
+            fields.push_punct(Default::default());
+        }
+        Pat::Struct(PatStruct {
+            attrs: vec![],
+            qself: None,
+            path: path.to_syn_path(),
+            brace_token: Default::default(),
+            fields,
+            rest: match has_rest {
+                true => Some(PatRest {
+                    attrs: vec![],
+                    dot2_token: Default::default(),
+                }),
+                false => None,
+            },
+        })
+    }
+
+    /// `first | second | ...`
+    pub fn or(patterns: Vec<Pat>) -> Pat {
+        Pat::Or(PatOr {
+            attrs: vec![],
+            leading_vert: None,
+            cases: patterns.into_iter().collect(),
+        })
+    }
+}
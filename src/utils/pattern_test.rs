@@ -0,0 +1,73 @@
+use crate::utils::expr::ExprBuilder;
+use crate::utils::path::Path;
+use crate::utils::pattern::PatternBuilder;
+use crate::utils::statement::Expr;
+use pretty_assertions::assert_eq;
+use quote::{quote, ToTokens};
+use rstest::rstest;
+use syn::{Lit, Pat};
+
+fn path(value: &str) -> Path {
+    Path::parse(value).unwrap()
+}
+
+#[rstest]
+fn patterns() {
+    let lit: Lit = syn::parse_quote!(0);
+    let cases: Vec<(Pat, proc_macro2::TokenStream)> = vec![
+        (PatternBuilder::wildcard(), quote!(_)),
+        (PatternBuilder::ident("value"), quote!(value)),
+        (PatternBuilder::ident_with_modifiers("value", true, true), quote!(ref mut value)),
+        (PatternBuilder::ident_with_modifiers("value", false, true), quote!(mut value)),
+        (PatternBuilder::binding("value", PatternBuilder::tuple_struct(path("Some"), vec![PatternBuilder::wildcard()])), quote!(value @ Some(_))),
+        (PatternBuilder::path(path("Shape::Empty")), quote!(Shape::Empty)),
+        (PatternBuilder::lit(lit.clone()), quote!(0)),
+        (PatternBuilder::tuple_struct(path("Shape::Circle"), vec![PatternBuilder::ident("radius"), PatternBuilder::wildcard()]), quote!(Shape::Circle(radius, _))),
+        (PatternBuilder::tuple(vec![PatternBuilder::ident("a")]), quote!((a,))),
+        (PatternBuilder::tuple(vec![PatternBuilder::ident("a"), PatternBuilder::wildcard()]), quote!((a, _))),
+        (
+            PatternBuilder::struct_(path("Point"), vec![("x", PatternBuilder::ident("x")), ("y", PatternBuilder::lit(lit.clone()))], true),
+            quote!(Point { x, y: 0, .. })
+        ),
+        (
+            PatternBuilder::struct_(path("Point"), vec![("x", PatternBuilder::ident("left"))], false),
+            quote!(Point { x: left })
+        ),
+        (PatternBuilder::or(vec![PatternBuilder::path(path("A")), PatternBuilder::path(path("B"))]), quote!(A | B)),
+    ];
+    for (pattern, expected_quote) in cases {
+        assert_eq!(expected_quote.to_string(), pattern.to_token_stream().to_string());
+    }
+}
+
+#[rstest]
+fn match_with_arms() {
+    let expr = ExprBuilder::match_(
+        Expr::Path(path("shape")),
+        vec![
+            ExprBuilder::arm(
+                PatternBuilder::tuple_struct(path("Shape::Circle"), vec![PatternBuilder::ident("radius")]),
+                Some(Expr::from(ExprBuilder::binary(Expr::Path(path("radius")), ">", ExprBuilder::lit_int("0")).unwrap())),
+                Expr::Path(path("radius")),
+            ),
+            ExprBuilder::arm(
+                PatternBuilder::struct_(path("Shape::Rectangle"), vec![("width", PatternBuilder::ident("width"))], true),
+                None,
+                Expr::Path(path("width")),
+            ),
+            ExprBuilder::arm(
+                PatternBuilder::or(vec![PatternBuilder::path(path("Shape::Empty")), PatternBuilder::wildcard()]),
+                None,
+                ExprBuilder::lit_int("0"),
+            ),
+        ],
+    );
+    let expected_expr: syn::Expr = syn::parse_quote! {
+        match shape {
+            Shape::Circle(radius) if radius > 0 => radius,
+            Shape::Rectangle { width, .. } => width,
+            Shape::Empty | _ => 0,
+        }
+    };
+    assert_eq!(expected_expr, expr);
+}
@@ -0,0 +1,395 @@
+use crate::utils::path::Path;
+use proc_macro2::Span;
+use syn::punctuated::Punctuated;
+use syn::{BareFnArg, Expr, GenericArgument, Lifetime, PathArguments, QSelf, ReturnType, Token, TraitBound, TraitBoundModifier, Type, TypeArray, TypeBareFn, TypeImplTrait, TypeParamBound, TypePath, TypeReference, TypeSlice, TypeTraitObject, TypeTuple};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SharedKind {
+    Rc,
+    Arc,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CellKind {
+    Cell,
+    RefCell,
+    Mutex,
+    RwLock,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MapKind {
+    HashMap,
+    BTreeMap,
+}
+
+/// Shape of a type, e.g. `Option<Rc<RefCell<u32>>>` is
+/// `Option(Shared(Rc, Cell(RefCell, Primitive("u32"))))`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum TypeShape {
+    Option(Box<TypeShape>),
+    /// `error` is `None` for aliases such as `io::Result<T>`.
+    Result {
+        ok: Box<TypeShape>,
+        error: Option<Box<TypeShape>>,
+    },
+    Box(Box<TypeShape>),
+    Shared(SharedKind, Box<TypeShape>),
+    Cell(CellKind, Box<TypeShape>),
+    Vec(Box<TypeShape>),
+    Map {
+        kind: MapKind,
+        key: Box<TypeShape>,
+        value: Box<TypeShape>,
+    },
+    Tuple(Vec<TypeShape>),
+    /// Array (`[T; N]`) or slice (`[T]`).
+    Array(Box<TypeShape>),
+    Reference {
+        is_mutable: bool,
+        inner: Box<TypeShape>,
+    },
+    Primitive(String),
+    Custom(Type),
+}
+
+const PRIMITIVES: [&str; 17] = [
+    "bool", "char", "str", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128",
+    "isize", "f32", "f64",
+];
+
+/// Fully qualified paths of the recognized types. A path matches when its segments are a suffix of
+/// one of them, e.g. `HashMap`, `collections::HashMap` and `::std::collections::HashMap`.
+const KNOWN_PATHS: [(&str, &[&str]); 12] = [
+    ("Option", &["std::option::Option", "core::option::Option"]),
+    ("Result", &["std::result::Result", "core::result::Result", "std::io::Result"]),
+    ("Box", &["std::boxed::Box", "alloc::boxed::Box"]),
+    ("Rc", &["std::rc::Rc", "alloc::rc::Rc"]),
+    ("Arc", &["std::sync::Arc", "alloc::sync::Arc"]),
+    ("Cell", &["std::cell::Cell", "core::cell::Cell"]),
+    ("RefCell", &["std::cell::RefCell", "core::cell::RefCell"]),
+    ("Mutex", &["std::sync::Mutex"]),
+    ("RwLock", &["std::sync::RwLock"]),
+    ("Vec", &["std::vec::Vec", "alloc::vec::Vec"]),
+    ("HashMap", &["std::collections::HashMap"]),
+    ("BTreeMap", &["std::collections::BTreeMap", "alloc::collections::BTreeMap"]),
+];
+
+impl TypeShape {
+    fn known_ident(path: &syn::Path) -> Option<&'static str> {
+        let segments = path.segments.iter()
+            .map(|segment| segment.ident.to_string())
+            .collect::<Vec<_>>();
+        KNOWN_PATHS.iter()
+            .find(|(_, known_paths)| {
+                known_paths.iter().any(|known_path| {
+                    let known_segments = known_path.split("::").collect::<Vec<_>>();
+                    match path.leading_colon.is_some() {
+                        true => known_segments == segments,
+                        false => known_segments.ends_with(&segments.iter().map(String::as_str).collect::<Vec<_>>()),
+                    }
+                })
+            })
+            .map(|(ident, _)| *ident)
+    }
+
+    fn primitive(path: &syn::Path) -> Option<String> {
+        let ident = path.segments.last()?.ident.to_string();
+        let is_primitive_path = match path.segments.len() {
+            1 => path.leading_colon.is_none(),
+            3 => ["std", "core"].iter().any(|crate_name| path.segments[0].ident == crate_name)
+                && path.segments[1].ident == "primitive",
+            _ => false,
+        };
+        match is_primitive_path && PRIMITIVES.contains(&ident.as_str()) {
+            true => Some(ident),
+            false => None,
+        }
+    }
+
+    fn type_arguments(path: &syn::Path) -> Vec<TypeShape> {
+        match &path.segments.last().unwrap().arguments {
+            PathArguments::AngleBracketed(value) => value.args.iter()
+                .filter_map(|argument| match argument {
+                    GenericArgument::Type(value) => Some(TypeShape::from(value)),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn from_path(type_: &Type, path: &syn::Path) -> TypeShape {
+        if let Some(value) = Self::primitive(path) {
+            return TypeShape::Primitive(value);
+        }
+        let ident = match Self::known_ident(path) {
+            Some(value) => value,
+            None => return TypeShape::Custom(type_.clone()),
+        };
+        let mut arguments = Self::type_arguments(path).into_iter().map(Box::new);
+        match (ident, arguments.len()) {
+            ("Option", 1) => TypeShape::Option(arguments.next().unwrap()),
+            ("Result", 1) => TypeShape::Result {
+                ok: arguments.next().unwrap(),
+                error: None,
+            },
+            ("Result", 2) => TypeShape::Result {
+                ok: arguments.next().unwrap(),
+                error: arguments.next(),
+            },
+            ("Box", 1) => TypeShape::Box(arguments.next().unwrap()),
+            ("Rc", 1) => TypeShape::Shared(SharedKind::Rc, arguments.next().unwrap()),
+            ("Arc", 1) => TypeShape::Shared(SharedKind::Arc, arguments.next().unwrap()),
+            ("Cell", 1) => TypeShape::Cell(CellKind::Cell, arguments.next().unwrap()),
+            ("RefCell", 1) => TypeShape::Cell(CellKind::RefCell, arguments.next().unwrap()),
+            ("Mutex", 1) => TypeShape::Cell(CellKind::Mutex, arguments.next().unwrap()),
+            ("RwLock", 1) => TypeShape::Cell(CellKind::RwLock, arguments.next().unwrap()),
+            ("Vec", 1) => TypeShape::Vec(arguments.next().unwrap()),
+            ("HashMap", 2 | 3) => TypeShape::Map {
+                kind: MapKind::HashMap,
+                key: arguments.next().unwrap(),
+                value: arguments.next().unwrap(),
+            },
+            ("BTreeMap", 2) => TypeShape::Map {
+                kind: MapKind::BTreeMap,
+                key: arguments.next().unwrap(),
+                value: arguments.next().unwrap(),
+            },
+            _ => TypeShape::Custom(type_.clone()),
+        }
+    }
+
+    /// Wrapped shape of a wrapper with a single argument (option, box, shared pointer, cell, vec,
+    /// array or reference).
+    pub fn inner(&self) -> Option<&TypeShape> {
+        match self {
+            TypeShape::Option(value) => Some(value),
+            TypeShape::Box(value) => Some(value),
+            TypeShape::Shared(_, value) => Some(value),
+            TypeShape::Cell(_, value) => Some(value),
+            TypeShape::Vec(value) => Some(value),
+            TypeShape::Array(value) => Some(value),
+            TypeShape::Reference { inner, .. } => Some(inner),
+            _ => None,
+        }
+    }
+
+    pub fn unwrap_option(&self) -> Option<&TypeShape> {
+        match self {
+            TypeShape::Option(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn unwrap_result(&self) -> Option<(&TypeShape, Option<&TypeShape>)> {
+        match self {
+            TypeShape::Result { ok, error } => Some((ok, error.as_deref())),
+            _ => None,
+        }
+    }
+
+    pub fn unwrap_map(&self) -> Option<(&TypeShape, &TypeShape)> {
+        match self {
+            TypeShape::Map { key, value, .. } => Some((key, value)),
+            _ => None,
+        }
+    }
+
+    /// Peel the indirections (box, shared pointers, cells and references) down to the pointed
+    /// shape, e.g. `u32` for `&Rc<RefCell<u32>>`.
+    pub fn peel(&self) -> &TypeShape {
+        match self {
+            TypeShape::Box(value) => value.peel(),
+            TypeShape::Shared(_, value) => value.peel(),
+            TypeShape::Cell(_, value) => value.peel(),
+            TypeShape::Reference { inner, .. } => inner.peel(),
+            _ => self,
+        }
+    }
+
+    /// Peel every wrapper with a single argument, e.g. `u32` for `Option<Vec<Box<u32>>>`.
+    pub fn peel_all(&self) -> &TypeShape {
+        match self.inner() {
+            Some(value) => value.peel_all(),
+            None => self,
+        }
+    }
+
+    pub fn is_primitive(&self) -> bool {
+        matches!(self, TypeShape::Primitive(_))
+    }
+
+    /// Path of a custom type, e.g. `crate::models::Model`.
+    pub fn custom_path(&self) -> Option<Path> {
+        match self {
+            TypeShape::Custom(Type::Path(value)) if value.qself.is_none() => Some(Path::from(&value.path)),
+            _ => None,
+        }
+    }
+}
+
+impl From<&Type> for TypeShape {
+    fn from(value: &Type) -> Self {
+        match value {
+            Type::Path(path) if path.qself.is_none() => TypeShape::from_path(value, &path.path),
+            Type::Tuple(tuple) => TypeShape::Tuple(tuple.elems.iter().map(TypeShape::from).collect()),
+            Type::Array(array) => TypeShape::Array(Box::new(TypeShape::from(&*array.elem))),
+            Type::Slice(slice) => TypeShape::Array(Box::new(TypeShape::from(&*slice.elem))),
+            Type::Reference(reference) => TypeShape::Reference {
+                is_mutable: reference.mutability.is_some(),
+                inner: Box::new(TypeShape::from(&*reference.elem)),
+            },
+            Type::Paren(paren) => TypeShape::from(&*paren.elem),
+            Type::Group(group) => TypeShape::from(&*group.elem),
+            _ => TypeShape::Custom(value.clone()),
+        }
+    }
+}
+
+impl From<Type> for TypeShape {
+    fn from(value: Type) -> Self {
+        TypeShape::from(&value)
+    }
+}
+
+/// Builder of `syn::Type` trees.
+pub struct TypeBuilder;
+
+impl TypeBuilder {
+    pub fn path(path: Path) -> Type {
+        Type::Path(TypePath {
+            qself: None,
+            path: path.to_syn_path(),
+        })
+    }
+
+    /// `&'lifetime mut inner`, the lifetime and mutability being optional.
+    pub fn reference(inner: Type, lifetime: Option<&str>, is_mutable: bool) -> Type {
+        let lifetime = lifetime.map(|lifetime| match lifetime.starts_with('\'') {
+            true => Lifetime::new(lifetime, Span::call_site()),
+            false => Lifetime::new(&format!("'{}", lifetime), Span::call_site()),
+        });
+        Type::Reference(TypeReference {
+            and_token: Default::default(),
+            lifetime,
+            mutability: match is_mutable {
+                true => Some(Default::default()),
+                false => None,
+            },
+            elem: Box::new(inner),
+        })
+    }
+
+    pub fn slice(element: Type) -> Type {
+        Type::Slice(TypeSlice {
+            bracket_token: Default::default(),
+            elem: Box::new(element),
+        })
+    }
+
+    /// `[element; length]`, the length being a literal or a const expression.
+    pub fn array(element: Type, length: Expr) -> Type {
+        Type::Array(TypeArray {
+            bracket_token: Default::default(),
+            elem: Box::new(element),
+            semi_token: Default::default(),
+            len: length,
+        })
+    }
+
+    /// Tuple of `elements`, the unit type when empty.
+    pub fn tuple(elements: Vec<Type>) -> Type {
+        let mut elements = elements.into_iter().collect::<Punctuated<Type, Token![,]>>();
+        if elements.len() == 1 {
+            elements.push_punct(Default::default());
+        }
+        Type::Tuple(TypeTuple {
+            paren_token: Default::default(),
+            elems: elements,
+        })
+    }
+
+    /// `fn(inputs) -> output`.
+    pub fn fn_pointer(inputs: Vec<Type>, output: Option<Type>) -> Type {
+        Type::BareFn(TypeBareFn {
+            lifetimes: None,
+            unsafety: None,
+            abi: None,
+            fn_token: Default::default(),
+            paren_token: Default::default(),
+            inputs: inputs.into_iter()
+                .map(|input| BareFnArg {
+                    attrs: vec![],
+                    name: None,
+                    ty: input,
+                })
+                .collect(),
+            variadic: None,
+            output: Self::return_type(output),
+        })
+    }
+
+    fn return_type(output: Option<Type>) -> ReturnType {
+        match output {
+            Some(value) => ReturnType::Type(Default::default(), Box::new(value)),
+            None => ReturnType::Default,
+        }
+    }
+
+    fn bounds(bounds: Vec<Path>) -> Punctuated<TypeParamBound, Token![+]> {
+        bounds.into_iter()
+            .map(|bound| TypeParamBound::Trait(TraitBound {
+                paren_token: None,
+                modifier: TraitBoundModifier::None,
+                lifetimes: None,
+                path: bound.to_syn_path(),
+            }))
+            .collect()
+    }
+
+    /// `impl Bound + ...`, e.g. `impl Iterator<Item = u8>`.
+    pub fn impl_trait(bounds: Vec<Path>) -> Type {
+        Type::ImplTrait(TypeImplTrait {
+            impl_token: Default::default(),
+            bounds: Self::bounds(bounds),
+        })
+    }
+
+    /// `dyn Bound + ...`, e.g. `dyn Error + Send + Sync`.
+    pub fn dyn_trait(bounds: Vec<Path>) -> Type {
+        Type::TraitObject(TypeTraitObject {
+            dyn_token: Some(Default::default()),
+            bounds: Self::bounds(bounds),
+        })
+    }
+
+    /// `<self_type as trait_>::associated`, e.g. `<T as Iterator>::Item`.
+    pub fn qualified(self_type: Type, trait_: Path, associated: Path) -> Type {
+        let mut path = trait_.to_syn_path();
+        let position = path.segments.len();
+        path.segments.extend(associated.iter().cloned());
+        Type::Path(TypePath {
+            qself: Some(QSelf {
+                lt_token: Default::default(),
+                ty: Box::new(self_type),
+                position,
+                as_token: Some(Default::default()),
+                gt_token: Default::default(),
+            }),
+            path,
+        })
+    }
+}
+
+impl From<Path> for Type {
+    fn from(value: Path) -> Self {
+        TypeBuilder::path(value)
+    }
+}
+
+impl From<&Path> for Type {
+    fn from(value: &Path) -> Self {
+        TypeBuilder::path(value.clone())
+    }
+}
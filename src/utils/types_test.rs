@@ -0,0 +1,135 @@
+use crate::test_utils::fixtures::*;
+use crate::utils::path::Path;
+use crate::utils::types::{CellKind, MapKind, SharedKind, TypeBuilder, TypeShape};
+use pretty_assertions::assert_eq;
+use quote::ToTokens;
+use rstest::rstest;
+use syn::Type;
+
+fn primitive(ident: &str) -> Box<TypeShape> {
+    Box::new(TypeShape::Primitive(ident.to_string()))
+}
+
+#[rstest]
+fn from_fixtures(
+    required_field: syn::Field,
+    optional_field: syn::Field,
+    optional_and_optional_field: syn::Field,
+    complex_field: syn::Field,
+) {
+    assert_eq!(*primitive("u32"), TypeShape::from(&required_field.ty));
+    assert_eq!(TypeShape::Option(primitive("u32")), TypeShape::from(&optional_field.ty));
+    assert_eq!(
+        TypeShape::Option(Box::new(TypeShape::Option(primitive("u32")))),
+        TypeShape::from(&optional_and_optional_field.ty)
+    );
+    assert_eq!(TypeShape::Custom(complex_field.ty.clone()), TypeShape::from(&complex_field.ty));
+}
+
+#[rstest]
+fn from_wrapper_fixtures(
+    boxed_field: syn::Field,
+    optional_and_boxed_field: syn::Field,
+    ref_counter_and_refcell_field: syn::Field,
+    vec_of_primitive_field: syn::Field,
+    map_of_primitive_field: syn::Field,
+) {
+    assert_eq!(TypeShape::Box(primitive("u32")), TypeShape::from(&boxed_field.ty));
+    assert_eq!(
+        TypeShape::Option(Box::new(TypeShape::Box(primitive("u32")))),
+        TypeShape::from(&optional_and_boxed_field.ty)
+    );
+    assert_eq!(
+        TypeShape::Shared(SharedKind::Rc, Box::new(TypeShape::Cell(CellKind::RefCell, primitive("u32")))),
+        TypeShape::from(&ref_counter_and_refcell_field.ty)
+    );
+    assert_eq!(TypeShape::Vec(primitive("u32")), TypeShape::from(&vec_of_primitive_field.ty));
+    assert_eq!(
+        TypeShape::Map { kind: MapKind::HashMap, key: primitive("u32"), value: primitive("u32") },
+        TypeShape::from(&map_of_primitive_field.ty)
+    );
+}
+
+#[rstest]
+#[case(syn::parse_quote!(::std::option::Option<u8>))]
+#[case(syn::parse_quote!(std::option::Option<u8>))]
+#[case(syn::parse_quote!(core::option::Option<u8>))]
+#[case(syn::parse_quote!(option::Option<u8>))]
+#[case(syn::parse_quote!(Option<std::primitive::u8>))]
+fn from_qualified_path(#[case] type_: Type) {
+    assert_eq!(TypeShape::Option(primitive("u8")), TypeShape::from(&type_));
+}
+
+#[rstest]
+#[case(syn::parse_quote!(::option::Option<u8>))]
+#[case(syn::parse_quote!(models::Option<u8>))]
+#[case(syn::parse_quote!(Option<u8, u8>))]
+#[case(syn::parse_quote!(fmt::Result))]
+fn from_custom_path(#[case] type_: Type) {
+    assert_eq!(TypeShape::Custom(type_.clone()), TypeShape::from(&type_));
+}
+
+#[rstest]
+fn from_compound_types() {
+    let type_: Type = syn::parse_quote!((&'a mut [u8], [bool; 4], io::Result<()>, Arc<Mutex<String>>));
+    let string: Type = syn::parse_quote!(String);
+    assert_eq!(
+        TypeShape::Tuple(vec![
+            TypeShape::Reference { is_mutable: true, inner: Box::new(TypeShape::Array(primitive("u8"))) },
+            TypeShape::Array(primitive("bool")),
+            TypeShape::Result { ok: Box::new(TypeShape::Tuple(vec![])), error: None },
+            TypeShape::Shared(SharedKind::Arc, Box::new(TypeShape::Cell(CellKind::Mutex, Box::new(TypeShape::Custom(string))))),
+        ]),
+        TypeShape::from(&type_)
+    );
+}
+
+#[rstest]
+fn unwrap_and_peel() {
+    let type_: Type = syn::parse_quote!(Option<&Rc<RefCell<crate::models::Model>>>);
+    let shape = TypeShape::from(&type_);
+    let inner = shape.unwrap_option().unwrap();
+    assert!(inner.unwrap_option().is_none());
+    assert_eq!(
+        Some(Path::parse("crate::models::Model").unwrap()),
+        inner.peel().custom_path()
+    );
+    assert_eq!(inner.peel(), shape.peel_all());
+    let type_: Type = syn::parse_quote!(Result<BTreeMap<String, Vec<u8>>, Error>);
+    let shape = TypeShape::from(&type_);
+    let (ok, error) = shape.unwrap_result().unwrap();
+    assert!(error.is_some());
+    let (_, value) = ok.unwrap_map().unwrap();
+    assert!(value.peel_all().is_primitive());
+}
+
+#[rstest]
+fn type_builder() {
+    fn path(value: &str) -> Path {
+        Path::parse(value).unwrap()
+    }
+    let cases: Vec<(Type, Type)> = vec![
+        (TypeBuilder::path(path("Vec<u8>")), syn::parse_quote!(Vec<u8>)),
+        (Type::from(path("::std::rc::Rc<u8>")), syn::parse_quote!(::std::rc::Rc<u8>)),
+        (TypeBuilder::reference(Type::from(path("str")), None, false), syn::parse_quote!(&str)),
+        (TypeBuilder::reference(Type::from(path("T")), Some("a"), true), syn::parse_quote!(&'a mut T)),
+        (TypeBuilder::reference(Type::from(path("T")), Some("'static"), false), syn::parse_quote!(&'static T)),
+        (TypeBuilder::slice(Type::from(path("u8"))), syn::parse_quote!([u8])),
+        (TypeBuilder::array(Type::from(path("u8")), syn::parse_quote!(4)), syn::parse_quote!([u8; 4])),
+        (TypeBuilder::tuple(vec![]), syn::parse_quote!(())),
+        (TypeBuilder::tuple(vec![Type::from(path("u8"))]), syn::parse_quote!((u8,))),
+        (TypeBuilder::tuple(vec![Type::from(path("u8")), Type::from(path("bool"))]), syn::parse_quote!((u8, bool))),
+        (TypeBuilder::fn_pointer(vec![Type::from(path("u8"))], Some(Type::from(path("bool")))), syn::parse_quote!(fn(u8) -> bool)),
+        (TypeBuilder::fn_pointer(vec![], None), syn::parse_quote!(fn())),
+        (TypeBuilder::impl_trait(vec![path("Iterator<Item = u8>")]), syn::parse_quote!(impl Iterator<Item = u8>)),
+        (TypeBuilder::dyn_trait(vec![path("Error"), path("Send")]), syn::parse_quote!(dyn Error + Send)),
+        (TypeBuilder::qualified(Type::from(path("T")), path("Iterator"), path("Item")), syn::parse_quote!(<T as Iterator>::Item)),
+        (
+            TypeBuilder::qualified(Type::from(path("Vec<u8>")), path("std::ops::Index<usize>"), path("Output")),
+            syn::parse_quote!(<Vec<u8> as std::ops::Index<usize>>::Output)
+        ),
+    ];
+    for (type_, expected_type) in cases {
+        assert_eq!(expected_type.to_token_stream().to_string(), type_.to_token_stream().to_string());
+    }
+}
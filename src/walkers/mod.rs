@@ -12,6 +12,7 @@ pub mod generics;
 pub mod fields;
 pub mod path;
 pub mod angle_bracketed_generic_arguments;
+pub mod scope;
 
 pub struct Context {
     pub(crate) type_predicate: Option<Box<dyn FnMut(&mut syn::Type) -> bool>>,
@@ -1,11 +1,36 @@
 use crate::walkers::angle_bracketed_generic_arguments::AngleBracketedGenericArgumentsWalker;
 use crate::walkers::type_::TypeWalker;
 use crate::walkers::Context;
-use syn::{PathArguments, ReturnType};
+use syn::{GenericArgument, PathArguments, ReturnType, Type};
 
 pub struct PathWalker;
 
 impl PathWalker {
+    /// Call `callback` on every type argument of the path segments, e.g. `u8` and `bool` in
+    /// `Vec<u8>::map(bool)`. Used by path predicates since the walker stops at their path.
+    pub(crate) fn for_each_argument_type(path: &mut syn::Path, mut callback: impl FnMut(&mut Type)) {
+        for segment in path.segments.iter_mut() {
+            match segment.arguments {
+                PathArguments::AngleBracketed(ref mut value) => {
+                    for argument in value.args.iter_mut() {
+                        if let GenericArgument::Type(value) = argument {
+                            callback(value);
+                        }
+                    }
+                }
+                PathArguments::Parenthesized(ref mut value) => {
+                    for argument in value.inputs.iter_mut() {
+                        callback(argument);
+                    }
+                    if let ReturnType::Type(_, ref mut value) = value.output {
+                        callback(value.as_mut());
+                    }
+                }
+                PathArguments::None => {}
+            }
+        }
+    }
+
     pub fn walk(
         path: &mut syn::Path,
         context: &mut Context
@@ -0,0 +1,1014 @@
+use crate::items::enum_item::EnumItem;
+use crate::items::fn_item::FnType;
+use crate::items::implementation_item::ImplementationItem;
+use crate::items::item::Item;
+use crate::items::macro_::MacroInvocationArguments;
+use crate::items::module_item::ModuleItem;
+use crate::items::struct_item::StructItem;
+use crate::items::symbol_table::{ModuleSymbols, Namespace, SymbolKind, SymbolTable};
+use crate::utils::path::Path;
+use quote::ToTokens;
+use std::collections::{HashMap, HashSet};
+use syn::{parse2, Block, Expr, ExprMethodCall, FieldPat, FieldValue, Fields, FnArg, GenericArgument, GenericParam, Generics, Ident, ImplItem, ItemEnum, ItemImpl, ItemStruct, Macro, Member, Pat, PathArguments, ReturnType, Signature, Stmt, TraitItem, Type, TypeParamBound, UnOp, WherePredicate};
+
+#[derive(Debug, Clone, Default)]
+struct Frame {
+    values: HashMap<String, Option<syn::Path>>,
+    types: HashSet<String>,
+    /// Items of a block an ident pattern may refer to (constants, statics, unit structs).
+    pattern_items: HashSet<String>,
+    self_type: Option<syn::Path>,
+}
+
+/// Lexical scope tracked by [`ScopeWalker`]: local bindings (let, fn params, closure params,
+/// match arms, ...), generic parameters and the `Self` type of the enclosing impl.
+#[derive(Debug, Clone, Default)]
+pub struct Scope {
+    frames: Vec<Frame>,
+}
+
+impl Scope {
+    /// Whether `ident` refers to a local binding (value namespace) or a generic parameter
+    /// (type namespace) rather than to an item.
+    pub fn is_local(&self, ident: &str, namespace: Namespace) -> bool {
+        self.frames.iter()
+            .any(|frame| {
+                match namespace {
+                    Namespace::Value => frame.values.contains_key(ident),
+                    Namespace::Type => frame.types.contains(ident),
+                    Namespace::Macro => false,
+                }
+            })
+    }
+
+    /// Type of a local binding when it is known from an annotation or its initializer.
+    pub fn binding_type(&self, ident: &str) -> Option<&syn::Path> {
+        self.frames.iter()
+            .rev()
+            .find_map(|frame| frame.values.get(ident))
+            .and_then(|value| value.as_ref())
+    }
+
+    pub fn self_type(&self) -> Option<&syn::Path> {
+        self.frames.iter()
+            .rev()
+            .find_map(|frame| frame.self_type.as_ref())
+    }
+
+    /// Replace a leading `Self` by the enclosing impl type.
+    pub fn resolve_self(&self, path: &syn::Path) -> syn::Path {
+        let is_self = path.segments.first()
+            .map(|segment| segment.ident == "Self")
+            .unwrap_or(false);
+        let self_type = match self.self_type() {
+            Some(value) if is_self => value,
+            _ => return path.clone(),
+        };
+        let mut resolved_path = self_type.clone();
+        for segment in path.segments.iter().skip(1) {
+            resolved_path.segments.push(segment.clone());
+        }
+        resolved_path
+    }
+
+    /// Path of the nominal type behind references, parentheses and `Self`.
+    pub fn type_path(&self, type_: &Type) -> Option<syn::Path> {
+        match type_ {
+            Type::Reference(value) => self.type_path(&value.elem),
+            Type::Paren(value) => self.type_path(&value.elem),
+            Type::Group(value) => self.type_path(&value.elem),
+            Type::Path(value) if value.qself.is_none() => Some(self.resolve_self(&value.path)),
+            _ => None,
+        }
+    }
+
+    fn push(&mut self) {
+        self.frames.push(Frame::default());
+    }
+
+    fn pop(&mut self) {
+        self.frames.pop();
+    }
+
+    fn innermost(&mut self) -> &mut Frame {
+        if self.frames.is_empty() {
+            self.push();
+        }
+        self.frames.last_mut().unwrap()
+    }
+
+    fn bind_value(&mut self, ident: &Ident, type_: Option<syn::Path>) {
+        self.innermost().values.insert(ident.to_string(), type_);
+    }
+
+    fn bind_type(&mut self, ident: &Ident) {
+        self.innermost().types.insert(ident.to_string());
+    }
+
+    fn set_self_type(&mut self, type_: Option<syn::Path>) {
+        self.innermost().self_type = type_;
+    }
+}
+
+/// Callbacks invoked by [`ScopeWalker`]. Every callback receives the scope active at the
+/// visited node and may mutate the node in place.
+pub trait ScopeVisitor {
+    /// Ident of an item definition (struct, enum, fn, const, static, trait, type alias, union or
+    /// macro_rules) along with the namespaces the item lives in.
+    fn visit_item_ident(&mut self, _ident: &mut Ident, _namespaces: &[Namespace], _scope: &Scope) {}
+
+    /// Path used in a type, expression, pattern or macro invocation. `namespace` is the namespace
+    /// of the last segment; leading segments always live in the type namespace.
+    fn visit_path(&mut self, _path: &mut syn::Path, _namespace: Namespace, _scope: &Scope) {}
+
+    /// Ident introduced by a pattern (let, fn param, closure param, match arm, ...).
+    fn visit_binding(&mut self, _ident: &mut Ident, _scope: &Scope) {}
+
+    /// Field declaration, struct literal field, struct pattern field or field access. `owner`
+    /// is the path of the struct (or enum variant) when it is known.
+    fn visit_field(&mut self, _owner: Option<&syn::Path>, _member: &mut Member, _scope: &Scope) {}
+
+    /// Variant declaration of the enum `owner`.
+    fn visit_variant(&mut self, _owner: &Ident, _variant: &mut Ident, _scope: &Scope) {}
+
+    /// Ident of a function, const or type declared in an impl block of `owner`.
+    fn visit_associated_item(
+        &mut self,
+        _owner: &syn::Path,
+        _trait_: Option<&syn::Path>,
+        _ident: &mut Ident,
+        _scope: &Scope,
+    ) {}
+
+    /// Method call. `receiver` is the path of the receiver type when it is known.
+    fn visit_method_call(&mut self, _receiver: Option<&syn::Path>, _call: &mut ExprMethodCall, _scope: &Scope) {}
+
+    /// Macro invocation whose arguments are not a list of expressions and are left unwalked.
+    fn visit_unparsed_macro(&mut self, _macro_: &Macro, _scope: &Scope) {}
+}
+
+pub struct ScopeWalker<'a, V: ScopeVisitor> {
+    scope: Scope,
+    visitor: &'a mut V,
+    /// Items of the module an ident pattern may refer to instead of introducing a binding.
+    pattern_items: HashSet<String>,
+}
+
+impl<'a, V: ScopeVisitor> ScopeWalker<'a, V> {
+    pub fn new(visitor: &'a mut V) -> Self {
+        Self {
+            scope: Scope::default(),
+            visitor,
+            pattern_items: HashSet::from(["None".to_string()]),
+        }
+    }
+
+    /// Load the items of `module` an ident pattern may refer to: constants, statics, unit and
+    /// tuple structs, unit variants of the enums imported with a glob and imports of such items.
+    /// Imports are resolved within `module` only, see [`ScopeWalker::enter_module_in`].
+    /// [`ScopeWalker::walk_module`] calls it, items walked on their own need it beforehand.
+    pub fn enter_module(&mut self, module: &ModuleItem) {
+        let symbol_table = SymbolTable {
+            modules: vec![ModuleSymbols::new(module)],
+        };
+        self.enter_module_in(module, &symbol_table);
+    }
+
+    /// Same as [`ScopeWalker::enter_module`] but resolves imports through the modules of
+    /// `symbol_table`. Imports of items outside the crate are assumed to be bindings.
+    pub fn enter_module_in(&mut self, module: &ModuleItem, symbol_table: &SymbolTable) {
+        let symbols = ModuleSymbols::new(module);
+        let items = symbols.symbols().iter()
+            .filter(|symbol| symbol.in_namespace(Namespace::Value))
+            .filter(|symbol| match symbol.kind {
+                SymbolKind::Struct | SymbolKind::Const | SymbolKind::Static => true,
+                SymbolKind::Import => Self::is_pattern_import(symbol_table, &module.file_name, &symbol.ident),
+                _ => false,
+            })
+            .map(|symbol| symbol.ident.clone());
+        let variants = symbols.globs().iter()
+            .filter_map(|glob| glob.last())
+            .filter_map(|segment| {
+                module.items.iter().find_map(|item| match item {
+                    Item::Enum(value) if value.item.ident == segment.ident => Some(&value.item),
+                    _ => None,
+                })
+            })
+            .flat_map(|item| item.variants.iter())
+            .filter(|variant| matches!(variant.fields, Fields::Unit))
+            .map(|variant| variant.ident.to_string())
+            .collect::<Vec<_>>();
+        self.pattern_items.extend(items.chain(variants));
+    }
+
+    /// Whether the import `ident` of `module` resolves to a crate constant, static, unit or
+    /// tuple struct, or enum variant.
+    fn is_pattern_import(symbol_table: &SymbolTable, module: &str, ident: &str) -> bool {
+        let path = match symbol_table.resolve_item(module, &Path::new(ident)) {
+            Some(value) => value,
+            None => return false,
+        };
+        let segments = path.iter()
+            .map(|segment| segment.ident.to_string())
+            .collect::<Vec<_>>();
+        if segments.len() < 2 || segments[0] != "crate" {
+            return false;
+        }
+        let (symbols, item_segments) = match symbol_table.module(&segments[1]) {
+            Some(value) if segments.len() > 2 => (value, &segments[2..]),
+            _ => match symbol_table.module("") {
+                Some(value) => (value, &segments[1..]),
+                None => return false,
+            },
+        };
+        match item_segments {
+            [item] => symbols.lookup_in(item, Namespace::Value)
+                .map(|symbol| matches!(symbol.kind, SymbolKind::Struct | SymbolKind::Const | SymbolKind::Static))
+                .unwrap_or(false),
+            [enum_, _] => symbols.lookup_in(enum_, Namespace::Type)
+                .map(|symbol| symbol.kind == SymbolKind::Enum)
+                .unwrap_or(false),
+            _ => false,
+        }
+    }
+
+    /// Whether an ident pattern refers to an item rather than introducing a binding.
+    fn is_pattern_item(&self, ident: &Ident) -> bool {
+        let ident = ident.to_string();
+        self.pattern_items.contains(&ident)
+            || self.scope.frames.iter().any(|frame| frame.pattern_items.contains(&ident))
+    }
+
+    pub fn walk_module(&mut self, module: &mut ModuleItem) {
+        self.enter_module(module);
+        for item in module.items.iter_mut() {
+            self.walk_item(item);
+        }
+    }
+
+    /// Walk `module` after [`ScopeWalker::enter_module_in`].
+    pub fn walk_module_in(&mut self, module: &mut ModuleItem, symbol_table: &SymbolTable) {
+        self.enter_module_in(module, symbol_table);
+        for item in module.items.iter_mut() {
+            self.walk_item(item);
+        }
+    }
+
+    pub fn walk_item(&mut self, item: &mut Item) {
+        match item {
+            Item::Struct(value) => self.walk_struct_item(value),
+            Item::Enum(value) => self.walk_enum_item(value),
+            Item::Fn(value) => {
+                match &mut value.item {
+                    FnType::Global(value) => {
+                        self.visitor.visit_item_ident(&mut value.sig.ident, &[Namespace::Value], &self.scope);
+                        self.walk_fn(&mut value.sig, &mut value.block);
+                    }
+                    FnType::Implementation(value) => {
+                        self.walk_fn(&mut value.sig, &mut value.block);
+                    }
+                }
+            }
+            Item::Other(value) => self.walk_syn_item(&mut value.item),
+        }
+    }
+
+    fn walk_struct_item(&mut self, item: &mut StructItem) {
+        self.walk_item_struct(&mut item.item);
+        self.walk_implementation_items(&mut item.impl_items);
+    }
+
+    fn walk_enum_item(&mut self, item: &mut EnumItem) {
+        self.walk_item_enum(&mut item.item);
+        self.walk_implementation_items(&mut item.impl_items);
+    }
+
+    fn walk_implementation_items(&mut self, items: &mut [ImplementationItem]) {
+        for item in items.iter_mut() {
+            self.walk_item_impl(&mut item.item);
+            *item = ImplementationItem::new(item.item.clone());
+        }
+    }
+
+    pub fn walk_syn_item(&mut self, item: &mut syn::Item) {
+        match item {
+            syn::Item::Const(value) => {
+                self.visitor.visit_item_ident(&mut value.ident, &[Namespace::Value], &self.scope);
+                self.scope.push();
+                self.walk_generics(&mut value.generics);
+                self.walk_type(&mut value.ty);
+                self.walk_expr(&mut value.expr);
+                self.scope.pop();
+            }
+            syn::Item::Enum(value) => self.walk_item_enum(value),
+            syn::Item::Fn(value) => {
+                self.visitor.visit_item_ident(&mut value.sig.ident, &[Namespace::Value], &self.scope);
+                self.walk_fn(&mut value.sig, &mut value.block);
+            }
+            syn::Item::Impl(value) => self.walk_item_impl(value),
+            syn::Item::Macro(value) => {
+                if let Some(ref mut ident) = value.ident {
+                    self.visitor.visit_item_ident(ident, &[Namespace::Macro], &self.scope);
+                }
+                else {
+                    self.walk_macro(&mut value.mac);
+                }
+            }
+            syn::Item::Mod(value) => {
+                if let Some((_, ref mut items)) = value.content {
+                    let scope = std::mem::take(&mut self.scope);
+                    for item in items.iter_mut() {
+                        self.walk_syn_item(item);
+                    }
+                    self.scope = scope;
+                }
+            }
+            syn::Item::Static(value) => {
+                self.visitor.visit_item_ident(&mut value.ident, &[Namespace::Value], &self.scope);
+                self.walk_type(&mut value.ty);
+                self.walk_expr(&mut value.expr);
+            }
+            syn::Item::Struct(value) => self.walk_item_struct(value),
+            syn::Item::Trait(value) => {
+                self.visitor.visit_item_ident(&mut value.ident, &[Namespace::Type], &self.scope);
+                self.scope.push();
+                self.walk_generics(&mut value.generics);
+                for bound in value.supertraits.iter_mut() {
+                    self.walk_type_param_bound(bound);
+                }
+                for item in value.items.iter_mut() {
+                    match item {
+                        TraitItem::Const(value) => {
+                            self.walk_generics(&mut value.generics);
+                            self.walk_type(&mut value.ty);
+                            if let Some((_, ref mut default)) = value.default {
+                                self.walk_expr(default);
+                            }
+                        }
+                        TraitItem::Fn(value) => {
+                            match value.default {
+                                Some(ref mut block) => self.walk_fn(&mut value.sig, block),
+                                None => {
+                                    self.scope.push();
+                                    self.walk_signature(&mut value.sig);
+                                    self.scope.pop();
+                                }
+                            }
+                        }
+                        TraitItem::Type(value) => {
+                            self.walk_generics(&mut value.generics);
+                            for bound in value.bounds.iter_mut() {
+                                self.walk_type_param_bound(bound);
+                            }
+                            if let Some((_, ref mut default)) = value.default {
+                                self.walk_type(default);
+                            }
+                        }
+                        TraitItem::Macro(value) => self.walk_macro(&mut value.mac),
+                        _ => {}
+                    }
+                }
+                self.scope.pop();
+            }
+            syn::Item::TraitAlias(value) => {
+                self.visitor.visit_item_ident(&mut value.ident, &[Namespace::Type], &self.scope);
+                self.scope.push();
+                self.walk_generics(&mut value.generics);
+                for bound in value.bounds.iter_mut() {
+                    self.walk_type_param_bound(bound);
+                }
+                self.scope.pop();
+            }
+            syn::Item::Type(value) => {
+                self.visitor.visit_item_ident(&mut value.ident, &[Namespace::Type], &self.scope);
+                self.scope.push();
+                self.walk_generics(&mut value.generics);
+                self.walk_type(&mut value.ty);
+                self.scope.pop();
+            }
+            syn::Item::Union(value) => {
+                self.visitor.visit_item_ident(&mut value.ident, &[Namespace::Type], &self.scope);
+                self.scope.push();
+                self.walk_generics(&mut value.generics);
+                let owner = syn::Path::from(value.ident.clone());
+                for field in value.fields.named.iter_mut() {
+                    self.walk_field_ident(&owner, &mut field.ident);
+                    self.walk_type(&mut field.ty);
+                }
+                self.scope.pop();
+            }
+            _ => {}
+        }
+    }
+
+    fn walk_item_struct(&mut self, item: &mut ItemStruct) {
+        let namespaces = match item.fields {
+            Fields::Named(_) => vec![Namespace::Type],
+            _ => vec![Namespace::Type, Namespace::Value],
+        };
+        self.visitor.visit_item_ident(&mut item.ident, &namespaces, &self.scope);
+        self.scope.push();
+        self.walk_generics(&mut item.generics);
+        let owner = syn::Path::from(item.ident.clone());
+        for field in item.fields.iter_mut() {
+            self.walk_field_ident(&owner, &mut field.ident);
+            self.walk_type(&mut field.ty);
+        }
+        self.scope.pop();
+    }
+
+    fn walk_item_enum(&mut self, item: &mut ItemEnum) {
+        self.visitor.visit_item_ident(&mut item.ident, &[Namespace::Type], &self.scope);
+        self.scope.push();
+        self.walk_generics(&mut item.generics);
+        for variant in item.variants.iter_mut() {
+            self.visitor.visit_variant(&item.ident, &mut variant.ident, &self.scope);
+            let mut owner = syn::Path::from(item.ident.clone());
+            owner.segments.push(variant.ident.clone().into());
+            for field in variant.fields.iter_mut() {
+                self.walk_field_ident(&owner, &mut field.ident);
+                self.walk_type(&mut field.ty);
+            }
+            if let Some((_, ref mut discriminant)) = variant.discriminant {
+                self.walk_expr(discriminant);
+            }
+        }
+        self.scope.pop();
+    }
+
+    fn walk_field_ident(&mut self, owner: &syn::Path, ident: &mut Option<Ident>) {
+        if let Some(value) = ident {
+            let mut member = Member::Named(value.clone());
+            self.visitor.visit_field(Some(owner), &mut member, &self.scope);
+            if let Member::Named(member) = member {
+                *value = member;
+            }
+        }
+    }
+
+    pub fn walk_item_impl(&mut self, item: &mut ItemImpl) {
+        self.scope.push();
+        self.walk_generics(&mut item.generics);
+        if let Some((_, ref mut path, _)) = item.trait_ {
+            self.walk_path(path, Namespace::Type);
+        }
+        self.walk_type(&mut item.self_ty);
+        let owner = self.scope.type_path(&item.self_ty);
+        self.scope.set_self_type(owner.clone());
+        let trait_ = item.trait_.as_ref().map(|(_, path, _)| path.clone());
+        for impl_item in item.items.iter_mut() {
+            match impl_item {
+                ImplItem::Const(value) => {
+                    if let Some(ref owner) = owner {
+                        self.visitor.visit_associated_item(owner, trait_.as_ref(), &mut value.ident, &self.scope);
+                    }
+                    self.scope.push();
+                    self.walk_generics(&mut value.generics);
+                    self.walk_type(&mut value.ty);
+                    self.walk_expr(&mut value.expr);
+                    self.scope.pop();
+                }
+                ImplItem::Fn(value) => {
+                    if let Some(ref owner) = owner {
+                        self.visitor.visit_associated_item(owner, trait_.as_ref(), &mut value.sig.ident, &self.scope);
+                    }
+                    self.walk_fn(&mut value.sig, &mut value.block);
+                }
+                ImplItem::Type(value) => {
+                    if let Some(ref owner) = owner {
+                        self.visitor.visit_associated_item(owner, trait_.as_ref(), &mut value.ident, &self.scope);
+                    }
+                    self.scope.push();
+                    self.walk_generics(&mut value.generics);
+                    self.walk_type(&mut value.ty);
+                    self.scope.pop();
+                }
+                ImplItem::Macro(value) => self.walk_macro(&mut value.mac),
+                _ => {}
+            }
+        }
+        self.scope.pop();
+    }
+
+    fn walk_fn(&mut self, signature: &mut Signature, block: &mut Block) {
+        self.scope.push();
+        self.walk_signature(signature);
+        self.walk_block(block);
+        self.scope.pop();
+    }
+
+    fn walk_signature(&mut self, signature: &mut Signature) {
+        self.walk_generics(&mut signature.generics);
+        for argument in signature.inputs.iter_mut() {
+            match argument {
+                FnArg::Receiver(value) => {
+                    self.walk_type(&mut value.ty);
+                    let self_type = self.scope.self_type().cloned();
+                    self.scope.bind_value(&Ident::new("self", value.self_token.span), self_type);
+                }
+                FnArg::Typed(value) => {
+                    self.walk_type(&mut value.ty);
+                    let type_ = self.scope.type_path(&value.ty);
+                    self.walk_pattern(&mut value.pat, type_);
+                }
+            }
+        }
+        if let Some(ref mut variadic) = signature.variadic {
+            if let Some((ref mut pattern, _)) = variadic.pat {
+                self.walk_pattern(pattern, None);
+            }
+        }
+        if let ReturnType::Type(_, ref mut type_) = signature.output {
+            self.walk_type(type_);
+        }
+    }
+
+    fn walk_generics(&mut self, generics: &mut Generics) {
+        for param in generics.params.iter() {
+            match param {
+                GenericParam::Type(value) => self.scope.bind_type(&value.ident),
+                GenericParam::Const(value) => self.scope.bind_value(&value.ident, None),
+                GenericParam::Lifetime(_) => {}
+            }
+        }
+        for param in generics.params.iter_mut() {
+            match param {
+                GenericParam::Type(value) => {
+                    for bound in value.bounds.iter_mut() {
+                        self.walk_type_param_bound(bound);
+                    }
+                    if let Some(ref mut default) = value.default {
+                        self.walk_type(default);
+                    }
+                }
+                GenericParam::Const(value) => {
+                    self.walk_type(&mut value.ty);
+                    if let Some(ref mut default) = value.default {
+                        self.walk_expr(default);
+                    }
+                }
+                GenericParam::Lifetime(_) => {}
+            }
+        }
+        if let Some(ref mut where_clause) = generics.where_clause {
+            for predicate in where_clause.predicates.iter_mut() {
+                if let WherePredicate::Type(value) = predicate {
+                    self.walk_type(&mut value.bounded_ty);
+                    for bound in value.bounds.iter_mut() {
+                        self.walk_type_param_bound(bound);
+                    }
+                }
+            }
+        }
+    }
+
+    fn walk_type_param_bound(&mut self, bound: &mut TypeParamBound) {
+        if let TypeParamBound::Trait(value) = bound {
+            self.walk_path(&mut value.path, Namespace::Type);
+        }
+    }
+
+    pub fn walk_type(&mut self, type_: &mut Type) {
+        match type_ {
+            Type::Array(value) => {
+                self.walk_type(&mut value.elem);
+                self.walk_expr(&mut value.len);
+            }
+            Type::BareFn(value) => {
+                for argument in value.inputs.iter_mut() {
+                    self.walk_type(&mut argument.ty);
+                }
+                if let ReturnType::Type(_, ref mut type_) = value.output {
+                    self.walk_type(type_);
+                }
+            }
+            Type::Group(value) => self.walk_type(&mut value.elem),
+            Type::ImplTrait(value) => {
+                for bound in value.bounds.iter_mut() {
+                    self.walk_type_param_bound(bound);
+                }
+            }
+            Type::Macro(value) => self.walk_macro(&mut value.mac),
+            Type::Paren(value) => self.walk_type(&mut value.elem),
+            Type::Path(value) => {
+                if let Some(ref mut qself) = value.qself {
+                    self.walk_type(&mut qself.ty);
+                }
+                self.walk_path(&mut value.path, Namespace::Type);
+            }
+            Type::Ptr(value) => self.walk_type(&mut value.elem),
+            Type::Reference(value) => self.walk_type(&mut value.elem),
+            Type::Slice(value) => self.walk_type(&mut value.elem),
+            Type::TraitObject(value) => {
+                for bound in value.bounds.iter_mut() {
+                    self.walk_type_param_bound(bound);
+                }
+            }
+            Type::Tuple(value) => {
+                for element in value.elems.iter_mut() {
+                    self.walk_type(element);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn walk_path(&mut self, path: &mut syn::Path, namespace: Namespace) {
+        self.visitor.visit_path(path, namespace, &self.scope);
+        for segment in path.segments.iter_mut() {
+            match segment.arguments {
+                PathArguments::AngleBracketed(ref mut value) => {
+                    for argument in value.args.iter_mut() {
+                        self.walk_generic_argument(argument);
+                    }
+                }
+                PathArguments::Parenthesized(ref mut value) => {
+                    for input in value.inputs.iter_mut() {
+                        self.walk_type(input);
+                    }
+                    if let ReturnType::Type(_, ref mut type_) = value.output {
+                        self.walk_type(type_);
+                    }
+                }
+                PathArguments::None => {}
+            }
+        }
+    }
+
+    fn walk_generic_argument(&mut self, argument: &mut GenericArgument) {
+        match argument {
+            GenericArgument::Type(value) => self.walk_type(value),
+            GenericArgument::Const(value) => self.walk_expr(value),
+            GenericArgument::AssocType(value) => self.walk_type(&mut value.ty),
+            GenericArgument::AssocConst(value) => self.walk_expr(&mut value.value),
+            GenericArgument::Constraint(value) => {
+                for bound in value.bounds.iter_mut() {
+                    self.walk_type_param_bound(bound);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn walk_macro(&mut self, macro_: &mut Macro) {
+        self.walk_path(&mut macro_.path, Namespace::Macro);
+        let mut arguments = match parse2::<MacroInvocationArguments>(macro_.tokens.clone()) {
+            Ok(value) => value.arguments,
+            Err(_) => return self.visitor.visit_unparsed_macro(macro_, &self.scope),
+        };
+        let original = arguments.clone();
+        for argument in arguments.iter_mut() {
+            self.walk_expr(argument);
+        }
+        if arguments != original {
+            macro_.tokens = arguments.to_token_stream();
+        }
+    }
+
+    pub fn walk_block(&mut self, block: &mut Block) {
+        self.scope.push();
+        for statement in block.stmts.iter() {
+            let ident = match statement {
+                Stmt::Item(syn::Item::Const(value)) => &value.ident,
+                Stmt::Item(syn::Item::Static(value)) => &value.ident,
+                Stmt::Item(syn::Item::Struct(value)) if !matches!(value.fields, Fields::Named(_)) => &value.ident,
+                _ => continue,
+            };
+            self.scope.innermost().pattern_items.insert(ident.to_string());
+        }
+        for statement in block.stmts.iter_mut() {
+            self.walk_statement(statement);
+        }
+        self.scope.pop();
+    }
+
+    pub fn walk_statement(&mut self, statement: &mut Stmt) {
+        match statement {
+            Stmt::Local(value) => {
+                let mut type_ = None;
+                if let Some(ref mut init) = value.init {
+                    self.walk_expr(&mut init.expr);
+                    if let Some((_, ref mut diverge)) = init.diverge {
+                        self.walk_expr(diverge);
+                    }
+                    type_ = self.infer_expr(&init.expr);
+                }
+                self.walk_pattern(&mut value.pat, type_);
+            }
+            Stmt::Item(value) => {
+                // nested items cannot see local bindings of the enclosing function
+                let scope = std::mem::take(&mut self.scope);
+                self.walk_syn_item(value);
+                self.scope = scope;
+            }
+            Stmt::Expr(value, _) => self.walk_expr(value),
+            Stmt::Macro(value) => self.walk_macro(&mut value.mac),
+        }
+    }
+
+    pub fn walk_pattern(&mut self, pattern: &mut Pat, type_: Option<syn::Path>) {
+        match pattern {
+            Pat::Const(value) => self.walk_block(&mut value.block),
+            Pat::Ident(value) => {
+                let is_binding = value.by_ref.is_some()
+                    || value.mutability.is_some()
+                    || value.subpat.is_some()
+                    || !self.is_pattern_item(&value.ident);
+                if is_binding {
+                    self.visitor.visit_binding(&mut value.ident, &self.scope);
+                    self.scope.bind_value(&value.ident, type_);
+                }
+                else {
+                    // the ident refers to a unit struct, a unit variant or a constant
+                    let mut path = syn::Path::from(value.ident.clone());
+                    self.visitor.visit_path(&mut path, Namespace::Value, &self.scope);
+                    if path.segments.len() == 1 && path.leading_colon.is_none() {
+                        value.ident = path.segments[0].ident.clone();
+                    }
+                }
+                if let Some((_, ref mut subpattern)) = value.subpat {
+                    self.walk_pattern(subpattern, None);
+                }
+            }
+            Pat::Macro(value) => self.walk_macro(&mut value.mac),
+            Pat::Or(value) => {
+                for case in value.cases.iter_mut() {
+                    self.walk_pattern(case, type_.clone());
+                }
+            }
+            Pat::Paren(value) => self.walk_pattern(&mut value.pat, type_),
+            Pat::Path(value) => {
+                if let Some(ref mut qself) = value.qself {
+                    self.walk_type(&mut qself.ty);
+                }
+                self.walk_path(&mut value.path, Namespace::Value);
+            }
+            Pat::Range(value) => {
+                if let Some(ref mut start) = value.start {
+                    self.walk_expr(start);
+                }
+                if let Some(ref mut end) = value.end {
+                    self.walk_expr(end);
+                }
+            }
+            Pat::Reference(value) => self.walk_pattern(&mut value.pat, type_),
+            Pat::Slice(value) => {
+                for element in value.elems.iter_mut() {
+                    self.walk_pattern(element, None);
+                }
+            }
+            Pat::Struct(value) => {
+                if let Some(ref mut qself) = value.qself {
+                    self.walk_type(&mut qself.ty);
+                }
+                let owner = self.scope.resolve_self(&value.path);
+                self.walk_path(&mut value.path, Namespace::Type);
+                for field in value.fields.iter_mut() {
+                    self.walk_field_pattern(&owner, field);
+                }
+            }
+            Pat::Tuple(value) => {
+                for element in value.elems.iter_mut() {
+                    self.walk_pattern(element, None);
+                }
+            }
+            Pat::TupleStruct(value) => {
+                if let Some(ref mut qself) = value.qself {
+                    self.walk_type(&mut qself.ty);
+                }
+                self.walk_path(&mut value.path, Namespace::Value);
+                for element in value.elems.iter_mut() {
+                    self.walk_pattern(element, None);
+                }
+            }
+            Pat::Type(value) => {
+                self.walk_type(&mut value.ty);
+                let type_ = self.scope.type_path(&value.ty);
+                self.walk_pattern(&mut value.pat, type_);
+            }
+            _ => {}
+        }
+    }
+
+    fn walk_field_pattern(&mut self, owner: &syn::Path, field: &mut FieldPat) {
+        self.visitor.visit_field(Some(owner), &mut field.member, &self.scope);
+        self.walk_pattern(&mut field.pat, None);
+        if field.colon_token.is_some() {
+            return;
+        }
+        // a shorthand field pattern must be expanded once member and binding diverge
+        let is_shorthand = match (&field.member, field.pat.as_ref()) {
+            (Member::Named(member), Pat::Ident(pattern)) => *member == pattern.ident,
+            _ => false,
+        };
+        if !is_shorthand {
+            field.colon_token = Some(Default::default());
+        }
+    }
+
+    fn walk_field_value(&mut self, owner: Option<&syn::Path>, field: &mut FieldValue) {
+        self.visitor.visit_field(owner, &mut field.member, &self.scope);
+        self.walk_expr(&mut field.expr);
+        if field.colon_token.is_some() {
+            return;
+        }
+        // a shorthand field initializer must be expanded once member and value diverge
+        let is_shorthand = match (&field.member, &field.expr) {
+            (Member::Named(member), Expr::Path(expr)) => expr.path.is_ident(member),
+            _ => false,
+        };
+        if !is_shorthand {
+            field.colon_token = Some(Default::default());
+        }
+    }
+
+    /// Path of the type an expression evaluates to, when it can be told without type inference.
+    fn infer_expr(&self, expr: &Expr) -> Option<syn::Path> {
+        match expr {
+            Expr::Cast(value) => self.scope.type_path(&value.ty),
+            Expr::Group(value) => self.infer_expr(&value.expr),
+            Expr::Paren(value) => self.infer_expr(&value.expr),
+            Expr::Path(value) if value.qself.is_none() => {
+                let ident = value.path.get_ident()?;
+                self.scope.binding_type(&ident.to_string()).cloned()
+            }
+            Expr::Reference(value) => self.infer_expr(&value.expr),
+            Expr::Struct(value) if value.qself.is_none() => Some(self.scope.resolve_self(&value.path)),
+            Expr::Unary(value) => {
+                match value.op {
+                    UnOp::Deref(_) => self.infer_expr(&value.expr),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    pub fn walk_expr(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Array(value) => {
+                for element in value.elems.iter_mut() {
+                    self.walk_expr(element);
+                }
+            }
+            Expr::Assign(value) => {
+                self.walk_expr(&mut value.left);
+                self.walk_expr(&mut value.right);
+            }
+            Expr::Async(value) => self.walk_block(&mut value.block),
+            Expr::Await(value) => self.walk_expr(&mut value.base),
+            Expr::Binary(value) => {
+                self.walk_expr(&mut value.left);
+                self.walk_expr(&mut value.right);
+            }
+            Expr::Block(value) => self.walk_block(&mut value.block),
+            Expr::Break(value) => {
+                if let Some(ref mut expr) = value.expr {
+                    self.walk_expr(expr);
+                }
+            }
+            Expr::Call(value) => {
+                self.walk_expr(&mut value.func);
+                for argument in value.args.iter_mut() {
+                    self.walk_expr(argument);
+                }
+            }
+            Expr::Cast(value) => {
+                self.walk_expr(&mut value.expr);
+                self.walk_type(&mut value.ty);
+            }
+            Expr::Closure(value) => {
+                self.scope.push();
+                for input in value.inputs.iter_mut() {
+                    self.walk_pattern(input, None);
+                }
+                if let ReturnType::Type(_, ref mut type_) = value.output {
+                    self.walk_type(type_);
+                }
+                self.walk_expr(&mut value.body);
+                self.scope.pop();
+            }
+            Expr::Const(value) => self.walk_block(&mut value.block),
+            Expr::Field(value) => {
+                self.walk_expr(&mut value.base);
+                let owner = self.infer_expr(&value.base);
+                self.visitor.visit_field(owner.as_ref(), &mut value.member, &self.scope);
+            }
+            Expr::ForLoop(value) => {
+                self.walk_expr(&mut value.expr);
+                self.scope.push();
+                self.walk_pattern(&mut value.pat, None);
+                self.walk_block(&mut value.body);
+                self.scope.pop();
+            }
+            Expr::Group(value) => self.walk_expr(&mut value.expr),
+            Expr::If(value) => {
+                // bindings of `if let` are only visible in the then branch
+                self.scope.push();
+                self.walk_expr(&mut value.cond);
+                self.walk_block(&mut value.then_branch);
+                self.scope.pop();
+                if let Some((_, ref mut else_branch)) = value.else_branch {
+                    self.walk_expr(else_branch);
+                }
+            }
+            Expr::Index(value) => {
+                self.walk_expr(&mut value.expr);
+                self.walk_expr(&mut value.index);
+            }
+            Expr::Let(value) => {
+                self.walk_expr(&mut value.expr);
+                let type_ = self.infer_expr(&value.expr);
+                self.walk_pattern(&mut value.pat, type_);
+            }
+            Expr::Loop(value) => self.walk_block(&mut value.body),
+            Expr::Macro(value) => self.walk_macro(&mut value.mac),
+            Expr::Match(value) => {
+                self.walk_expr(&mut value.expr);
+                let type_ = self.infer_expr(&value.expr);
+                for arm in value.arms.iter_mut() {
+                    self.scope.push();
+                    self.walk_pattern(&mut arm.pat, type_.clone());
+                    if let Some((_, ref mut guard)) = arm.guard {
+                        self.walk_expr(guard);
+                    }
+                    self.walk_expr(&mut arm.body);
+                    self.scope.pop();
+                }
+            }
+            Expr::MethodCall(value) => {
+                self.walk_expr(&mut value.receiver);
+                let receiver = self.infer_expr(&value.receiver);
+                self.visitor.visit_method_call(receiver.as_ref(), value, &self.scope);
+                if let Some(ref mut turbofish) = value.turbofish {
+                    for argument in turbofish.args.iter_mut() {
+                        self.walk_generic_argument(argument);
+                    }
+                }
+                for argument in value.args.iter_mut() {
+                    self.walk_expr(argument);
+                }
+            }
+            Expr::Paren(value) => self.walk_expr(&mut value.expr),
+            Expr::Path(value) => {
+                if let Some(ref mut qself) = value.qself {
+                    self.walk_type(&mut qself.ty);
+                }
+                self.walk_path(&mut value.path, Namespace::Value);
+            }
+            Expr::Range(value) => {
+                if let Some(ref mut start) = value.start {
+                    self.walk_expr(start);
+                }
+                if let Some(ref mut end) = value.end {
+                    self.walk_expr(end);
+                }
+            }
+            Expr::RawAddr(value) => self.walk_expr(&mut value.expr),
+            Expr::Reference(value) => self.walk_expr(&mut value.expr),
+            Expr::Repeat(value) => {
+                self.walk_expr(&mut value.expr);
+                self.walk_expr(&mut value.len);
+            }
+            Expr::Return(value) => {
+                if let Some(ref mut expr) = value.expr {
+                    self.walk_expr(expr);
+                }
+            }
+            Expr::Struct(value) => {
+                if let Some(ref mut qself) = value.qself {
+                    self.walk_type(&mut qself.ty);
+                }
+                let owner = self.scope.resolve_self(&value.path);
+                self.walk_path(&mut value.path, Namespace::Type);
+                for field in value.fields.iter_mut() {
+                    self.walk_field_value(Some(&owner), field);
+                }
+                if let Some(ref mut rest) = value.rest {
+                    self.walk_expr(rest);
+                }
+            }
+            Expr::Try(value) => self.walk_expr(&mut value.expr),
+            Expr::TryBlock(value) => self.walk_block(&mut value.block),
+            Expr::Tuple(value) => {
+                for element in value.elems.iter_mut() {
+                    self.walk_expr(element);
+                }
+            }
+            Expr::Unary(value) => self.walk_expr(&mut value.expr),
+            Expr::Unsafe(value) => self.walk_block(&mut value.block),
+            Expr::While(value) => {
+                self.scope.push();
+                self.walk_expr(&mut value.cond);
+                self.walk_block(&mut value.body);
+                self.scope.pop();
+            }
+            Expr::Yield(value) => {
+                if let Some(ref mut expr) = value.expr {
+                    self.walk_expr(expr);
+                }
+            }
+            _ => {}
+        }
+    }
+}